@@ -0,0 +1,90 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::journal::write_atomic;
+use crate::parser::{ConversionOptions, ConversionSummary};
+
+/// Holds one JSON entry per converted source, under a directory named after the first two characters of its key
+pub const CACHE_DIRECTORY: &str = ".type-rs-cache";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    source: String,
+    summary: ConversionSummary,
+}
+
+/// Remembers the result of `add_types` for file contents we've seen before so unchanged files aren't parsed again.
+/// The cache is only an optimization: entries that can't be read or written are treated as missing.
+#[derive(Debug)]
+pub struct Cache {
+    directory: PathBuf,
+}
+
+impl Cache {
+    pub fn new(directory: &Path) -> Cache {
+        Cache {
+            directory: directory.to_path_buf(),
+        }
+    }
+
+    /// The cache inside [`CACHE_DIRECTORY`] of `directory`
+    pub fn in_directory(directory: &Path) -> Cache {
+        Cache::new(&directory.join(CACHE_DIRECTORY))
+    }
+
+    /// Entries depend on the version of type-rs and the effective configuration as well as on the contents,
+    /// so upgrading or changing a setting never returns a stale result
+    pub fn key(contents: &str, options: &ConversionOptions) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(env!("CARGO_PKG_VERSION"));
+        hasher.update([0]);
+        hasher.update(serde_json::to_vec(options).expect("conversion options always serialize"));
+        hasher.update([0]);
+        hasher.update(contents);
+
+        format!("{:x}", hasher.finalize())
+    }
+
+    pub fn get(&self, key: &str) -> Option<(String, ConversionSummary)> {
+        let path = self.entry_path(key);
+        let contents = fs::read_to_string(&path).ok()?;
+        match serde_json::from_str::<CacheEntry>(&contents) {
+            Ok(entry) => {
+                debug!("Found {key} in the cache");
+                Some((entry.source, entry.summary))
+            }
+            Err(err) => {
+                warn!("Ignoring invalid cache entry {path:?}: {err}");
+                None
+            }
+        }
+    }
+
+    pub fn insert(&self, key: &str, source: &str, summary: &ConversionSummary) {
+        let path = self.entry_path(key);
+        let entry = CacheEntry {
+            source: source.to_string(),
+            summary: summary.clone(),
+        };
+
+        let result = serde_json::to_vec(&entry)
+            .map_err(Into::into)
+            .and_then(|json| {
+                fs::create_dir_all(path.parent().unwrap_or(&self.directory))?;
+                write_atomic(&path, &json)
+            });
+        if let Err(err) = result {
+            warn!("Unable to write cache entry {path:?}: {err}");
+        }
+    }
+
+    /// Entries are spread over subdirectories named after the first two characters of the key to keep directories small
+    fn entry_path(&self, key: &str) -> PathBuf {
+        let prefix = key.get(..2).unwrap_or(key);
+        self.directory.join(prefix).join(format!("{key}.json"))
+    }
+}
@@ -0,0 +1,387 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use inflector::Inflector;
+use log::debug;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::parser::{ConversionOptions, InferenceRules};
+
+pub const CONFIG_FILE_NAME: &str = "type-rs.toml";
+/// Key under which the configuration can be embedded in a `package.json` instead
+pub const PACKAGE_JSON_KEY: &str = "type-rs";
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("unable to read {path:?}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("invalid configuration in {path:?}: {message}")]
+    Invalid { path: PathBuf, message: String },
+    #[error("invalid glob pattern in the configuration of {path:?}: {source}")]
+    Glob {
+        path: PathBuf,
+        source: globset::Error,
+    },
+}
+
+/// How much we trust ourselves to fill in types we couldn't infer
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Strictness {
+    /// Fall back to `any`
+    #[default]
+    Loose,
+    /// Fall back to `unknown` so every guess has to be looked at
+    Strict,
+}
+
+impl Strictness {
+    pub fn fallback_type(&self) -> FallbackType {
+        match self {
+            Strictness::Loose => FallbackType::Any,
+            Strictness::Strict => FallbackType::Unknown,
+        }
+    }
+}
+
+/// What to annotate code with when nothing better can be inferred
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FallbackType {
+    Any,
+    Unknown,
+    /// Leave the code unannotated so the compiler reports it with `noImplicitAny`.
+    /// Fields of generated interfaces still need a type and use `any`.
+    None,
+}
+
+impl FallbackType {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            FallbackType::Any | FallbackType::None => "any",
+            FallbackType::Unknown => "unknown",
+        }
+    }
+}
+
+impl FromStr for FallbackType {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "any" => Ok(FallbackType::Any),
+            "unknown" => Ok(FallbackType::Unknown),
+            "none" => Ok(FallbackType::None),
+            _ => Err(format!(
+                "unknown fallback type `{value}`, expected `unknown`, `any` or `none`"
+            )),
+        }
+    }
+}
+
+/// Naming scheme of the interfaces we generate
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Naming {
+    /// `options` becomes `Options`
+    #[default]
+    PascalCase,
+    /// `options` becomes `IOptions`
+    Prefixed,
+}
+
+impl Naming {
+    pub fn interface_name(&self, name: &str) -> String {
+        match self {
+            Naming::PascalCase => name.to_pascal_case(),
+            Naming::Prefixed => format!("I{}", name.to_pascal_case()),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OutputFormat {
+    /// Replace the original files
+    #[default]
+    Write,
+    /// Print a unified diff for every file
+    Diff,
+    /// Print a summary of the planned changes
+    DryRun,
+}
+
+/// Enables or disables individual inference rules. Anything left out is inherited.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Rules {
+    pub parameters: Option<bool>,
+    pub variables: Option<bool>,
+    pub catch_clauses: Option<bool>,
+    pub return_types: Option<bool>,
+    pub react_components: Option<bool>,
+    pub interfaces: Option<bool>,
+}
+
+impl Rules {
+    fn inherit(self, parent: &Rules) -> Rules {
+        Rules {
+            parameters: self.parameters.or(parent.parameters),
+            variables: self.variables.or(parent.variables),
+            catch_clauses: self.catch_clauses.or(parent.catch_clauses),
+            return_types: self.return_types.or(parent.return_types),
+            react_components: self.react_components.or(parent.react_components),
+            interfaces: self.interfaces.or(parent.interfaces),
+        }
+    }
+
+    fn to_inference_rules(&self) -> InferenceRules {
+        let defaults = InferenceRules::default();
+        InferenceRules {
+            parameters: self.parameters.unwrap_or(defaults.parameters),
+            variables: self.variables.unwrap_or(defaults.variables),
+            catch_clauses: self.catch_clauses.unwrap_or(defaults.catch_clauses),
+            return_types: self.return_types.unwrap_or(defaults.return_types),
+            react_components: self.react_components.unwrap_or(defaults.react_components),
+            interfaces: self.interfaces.unwrap_or(defaults.interfaces),
+        }
+    }
+}
+
+/// The contents of a single `type-rs.toml` file or `"type-rs"` entry in `package.json`.
+/// Glob patterns are relative to the directory containing the configuration.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Config {
+    pub include: Option<Vec<String>>,
+    pub exclude: Vec<String>,
+    pub strictness: Option<Strictness>,
+    /// Overrides the fallback type of `strictness`
+    pub fallback_type: Option<FallbackType>,
+    /// Fallback type of catch clause variables, e.g. `unknown` to match `useUnknownInCatchVariables`.
+    /// Defaults to `fallback-type`
+    pub catch_clause_type: Option<FallbackType>,
+    pub naming: Option<Naming>,
+    pub output: Option<OutputFormat>,
+    pub rules: Rules,
+}
+
+impl Config {
+    pub fn from_toml(path: &Path, contents: &str) -> Result<Config, ConfigError> {
+        toml::from_str(contents).map_err(|err| ConfigError::Invalid {
+            path: path.to_path_buf(),
+            message: err.to_string(),
+        })
+    }
+
+    /// Returns `None` if the `package.json` has no `"type-rs"` entry
+    pub fn from_package_json(path: &Path, contents: &str) -> Result<Option<Config>, ConfigError> {
+        let invalid = |err: serde_json::Error| ConfigError::Invalid {
+            path: path.to_path_buf(),
+            message: err.to_string(),
+        };
+
+        let mut package: serde_json::Value = serde_json::from_str(contents).map_err(invalid)?;
+        match package.get_mut(PACKAGE_JSON_KEY) {
+            Some(entry) => serde_json::from_value(entry.take())
+                .map(Some)
+                .map_err(invalid),
+            None => Ok(None),
+        }
+    }
+
+    /// Reads the configuration that lives in `directory` itself. `type-rs.toml` takes precedence over `package.json`.
+    pub fn load(directory: &Path) -> Result<Option<Config>, ConfigError> {
+        let toml_path = directory.join(CONFIG_FILE_NAME);
+        if toml_path.is_file() {
+            return Config::from_toml(&toml_path, &read(&toml_path)?).map(Some);
+        }
+
+        let package_path = directory.join("package.json");
+        if package_path.is_file() {
+            return Config::from_package_json(&package_path, &read(&package_path)?);
+        }
+
+        Ok(None)
+    }
+
+    /// Settings of the nested configuration win, exclusions of both configurations apply
+    fn inherit(self, parent: &Config) -> Config {
+        Config {
+            include: self.include.or_else(|| parent.include.clone()),
+            exclude: self.exclude,
+            strictness: self.strictness.or(parent.strictness),
+            fallback_type: self.fallback_type.or(parent.fallback_type),
+            catch_clause_type: self.catch_clause_type.or(parent.catch_clause_type),
+            naming: self.naming.or(parent.naming),
+            output: self.output.or(parent.output),
+            rules: self.rules.inherit(&parent.rules),
+        }
+    }
+}
+
+fn read(path: &Path) -> Result<String, ConfigError> {
+    fs::read_to_string(path).map_err(|source| ConfigError::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+/// Glob patterns together with the directory they are relative to
+#[derive(Debug, Clone)]
+struct ScopedGlobs {
+    base: PathBuf,
+    globs: GlobSet,
+}
+
+impl ScopedGlobs {
+    fn new(base: &Path, patterns: &[String]) -> Result<ScopedGlobs, ConfigError> {
+        let to_error = |source| ConfigError::Glob {
+            path: base.to_path_buf(),
+            source,
+        };
+
+        let mut builder = GlobSetBuilder::new();
+        for pattern in patterns {
+            builder.add(Glob::new(pattern).map_err(to_error)?);
+        }
+
+        Ok(ScopedGlobs {
+            base: base.to_path_buf(),
+            globs: builder.build().map_err(to_error)?,
+        })
+    }
+
+    fn is_match(&self, path: &Path) -> bool {
+        path.strip_prefix(&self.base)
+            .is_ok_and(|relative| self.globs.is_match(relative))
+    }
+}
+
+/// The configuration that applies to a directory after combining it with all of its ancestors
+#[derive(Debug, Clone, Default)]
+pub struct ResolvedConfig {
+    pub config: Config,
+    include: Option<ScopedGlobs>,
+    excludes: Vec<ScopedGlobs>,
+}
+
+impl ResolvedConfig {
+    fn inherit(
+        parent: &ResolvedConfig,
+        directory: &Path,
+        config: Config,
+    ) -> Result<ResolvedConfig, ConfigError> {
+        let include = match &config.include {
+            Some(patterns) => Some(ScopedGlobs::new(directory, patterns)?),
+            None => parent.include.clone(),
+        };
+
+        let mut excludes = parent.excludes.clone();
+        if !config.exclude.is_empty() {
+            excludes.push(ScopedGlobs::new(directory, &config.exclude)?);
+        }
+
+        Ok(ResolvedConfig {
+            config: config.inherit(&parent.config),
+            include,
+            excludes,
+        })
+    }
+
+    /// `path` has to be canonicalized since the globs are matched relative to the configuration's directory
+    pub fn is_excluded(&self, path: &Path) -> bool {
+        self.excludes.iter().any(|excludes| excludes.is_match(path))
+    }
+
+    pub fn is_included(&self, path: &Path) -> bool {
+        match &self.include {
+            Some(include) => include.is_match(path),
+            None => true,
+        }
+    }
+
+    pub fn output(&self) -> OutputFormat {
+        self.config.output.unwrap_or_default()
+    }
+
+    pub fn conversion_options(&self) -> ConversionOptions {
+        ConversionOptions {
+            strictness: self.config.strictness.unwrap_or_default(),
+            fallback_type: self.config.fallback_type,
+            catch_clause_type: self.config.catch_clause_type,
+            naming: self.config.naming.unwrap_or_default(),
+            rules: self.config.rules.to_inference_rules(),
+        }
+    }
+}
+
+/// Discovers and caches the configuration of every directory we visit
+#[derive(Debug, Default)]
+pub struct ConfigResolver {
+    cache: Mutex<HashMap<PathBuf, Arc<ResolvedConfig>>>,
+    /// Settings passed on the command line, which win over every configuration file
+    overrides: Option<Config>,
+}
+
+impl ConfigResolver {
+    pub fn new() -> ConfigResolver {
+        ConfigResolver::default()
+    }
+
+    pub fn with_overrides(mut self, overrides: Config) -> ConfigResolver {
+        self.overrides = Some(overrides);
+        self
+    }
+
+    /// Combines the configurations from the filesystem root down to `directory`
+    pub fn resolve(&self, directory: &Path) -> Result<Arc<ResolvedConfig>, ConfigError> {
+        let directory = fs::canonicalize(directory).map_err(|source| ConfigError::Io {
+            path: directory.to_path_buf(),
+            source,
+        })?;
+
+        let resolved = self.resolve_canonical(&directory)?;
+        Ok(match &self.overrides {
+            Some(overrides) => Arc::new(ResolvedConfig {
+                config: overrides.clone().inherit(&resolved.config),
+                ..ResolvedConfig::clone(&resolved)
+            }),
+            None => resolved,
+        })
+    }
+
+    fn resolve_canonical(&self, directory: &Path) -> Result<Arc<ResolvedConfig>, ConfigError> {
+        if let Some(resolved) = self.cache.lock().unwrap().get(directory) {
+            return Ok(Arc::clone(resolved));
+        }
+
+        let parent = match directory.parent() {
+            Some(parent) => self.resolve_canonical(parent)?,
+            None => Arc::new(ResolvedConfig::default()),
+        };
+
+        let resolved = match Config::load(directory)? {
+            Some(config) => {
+                debug!("Found configuration in {directory:?}: {config:?}");
+                Arc::new(ResolvedConfig::inherit(&parent, directory, config)?)
+            }
+            None => parent,
+        };
+
+        self.cache
+            .lock()
+            .unwrap()
+            .insert(directory.to_path_buf(), Arc::clone(&resolved));
+
+        Ok(resolved)
+    }
+}
@@ -0,0 +1,185 @@
+use std::ops::Range;
+
+use rslint_parser::TextRange;
+
+use crate::{
+    config::{FallbackType, Naming, Strictness},
+    error::TypeRsError,
+    parser::{infer_types, ConversionOptions, ConversionSummary, InferenceRules},
+    text_editor::TextEdit,
+    type_definition::TypeDefinition,
+    validation::{syntax_errors, validate_output},
+};
+
+/// How to decide whether the output is TSX
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum JsxHandling {
+    /// TSX if the source imports React, TypeScript otherwise
+    #[default]
+    Detect,
+    /// Always TSX, e.g. for `.jsx` files
+    Tsx,
+    /// Always TypeScript
+    TypeScript,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Warning,
+    Information,
+}
+
+/// Something worth knowing about a conversion which didn't stop it from succeeding
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    /// Byte offsets in the original source
+    pub span: Range<usize>,
+}
+
+impl Diagnostic {
+    pub(crate) fn fallback(subject: String, fallback_type: &str, span: TextRange) -> Diagnostic {
+        Diagnostic {
+            severity: Severity::Information,
+            message: format!(
+                "Nothing better than `{fallback_type}` could be inferred for {subject}"
+            ),
+            span: span.start().into()..span.end().into(),
+        }
+    }
+}
+
+/// Text inserted by the conversion
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edit {
+    /// Byte offset in the original source
+    pub offset: usize,
+    /// Byte offsets of the inserted text in the converted source
+    pub span: Range<usize>,
+    pub text: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct ConversionResult {
+    pub source: String,
+    /// `ts` or `tsx`
+    pub extension: &'static str,
+    /// Ordered by their position in the source
+    pub edits: Vec<Edit>,
+    /// The interfaces which were generated, in the order they were created
+    pub type_definitions: Vec<TypeDefinition>,
+    pub diagnostics: Vec<Diagnostic>,
+    pub summary: ConversionSummary,
+}
+
+/// Converts JavaScript sources to TypeScript for tools that embed type-rs.
+///
+/// ```
+/// use type_rs::config::Strictness;
+/// use type_rs::converter::Converter;
+///
+/// let result = Converter::default()
+///     .with_strictness(Strictness::Strict)
+///     .convert("function f(a) {}")
+///     .unwrap();
+/// assert_eq!(result.source, "function f(a: unknown): void {}");
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct Converter {
+    options: ConversionOptions,
+    jsx: JsxHandling,
+}
+
+impl Converter {
+    pub fn with_options(mut self, options: ConversionOptions) -> Converter {
+        self.options = options;
+        self
+    }
+
+    pub fn with_strictness(mut self, strictness: Strictness) -> Converter {
+        self.options.strictness = strictness;
+        self
+    }
+
+    pub fn with_fallback_type(mut self, fallback_type: FallbackType) -> Converter {
+        self.options.fallback_type = Some(fallback_type);
+        self
+    }
+
+    pub fn with_catch_clause_type(mut self, catch_clause_type: FallbackType) -> Converter {
+        self.options.catch_clause_type = Some(catch_clause_type);
+        self
+    }
+
+    pub fn with_naming(mut self, naming: Naming) -> Converter {
+        self.options.naming = naming;
+        self
+    }
+
+    pub fn with_rules(mut self, rules: InferenceRules) -> Converter {
+        self.options.rules = rules;
+        self
+    }
+
+    pub fn with_jsx(mut self, jsx: JsxHandling) -> Converter {
+        self.jsx = jsx;
+        self
+    }
+
+    pub fn options(&self) -> &ConversionOptions {
+        &self.options
+    }
+
+    pub fn target_extension(&self, source: &str) -> &'static str {
+        match self.jsx {
+            JsxHandling::Tsx => "tsx",
+            JsxHandling::TypeScript => "ts",
+            JsxHandling::Detect if source.contains("import React") => "tsx",
+            JsxHandling::Detect => "ts",
+        }
+    }
+
+    /// Adds the type annotations to `source`. Fails rather than return output that introduced syntax errors.
+    pub fn convert(&self, source: &str) -> Result<ConversionResult, TypeRsError> {
+        let mut inference = infer_types(source.to_string(), &self.options)?;
+        let new_source = inference.editor.apply();
+        validate_output(source, &new_source)?;
+
+        // `apply` sorted the changes, so every insertion shifts the ones after it
+        let mut shift = 0;
+        let edits = inference
+            .editor
+            .changes()
+            .iter()
+            .map(|change| {
+                let start = change.offset + shift;
+                shift += change.text.len();
+                Edit {
+                    offset: change.offset,
+                    span: start..start + change.text.len(),
+                    text: change.text.clone(),
+                }
+            })
+            .collect();
+
+        let mut diagnostics: Vec<Diagnostic> = syntax_errors(source)
+            .into_iter()
+            .map(|error| Diagnostic {
+                severity: Severity::Warning,
+                message: error.message,
+                span: error.span,
+            })
+            .collect();
+        diagnostics.append(&mut inference.diagnostics);
+
+        Ok(ConversionResult {
+            extension: self.target_extension(source),
+            source: new_source,
+            edits,
+            type_definitions: inference.type_definitions,
+            diagnostics,
+            summary: inference.summary,
+        })
+    }
+}
@@ -0,0 +1,56 @@
+use std::path::Path;
+use std::{env, fs};
+
+use similar::TextDiff;
+
+/// Creates a git-style unified diff which renames `original_path` to `new_path` and applies the content changes.
+/// The output can be fed straight into `git apply` or `patch -p1`.
+pub fn create_unified_diff(
+    original_path: &Path,
+    new_path: &Path,
+    original_source: &str,
+    new_source: &str,
+) -> String {
+    let original_path = to_diff_path(original_path);
+    let new_path = to_diff_path(new_path);
+    let mut buf = format!("diff --git a/{original_path} b/{new_path}\n");
+
+    if original_path != new_path {
+        buf.push_str(format!("rename from {original_path}\nrename to {new_path}\n").as_str());
+    }
+
+    if original_source != new_source {
+        let diff = TextDiff::from_lines(original_source, new_source);
+        buf += diff
+            .unified_diff()
+            .header(
+                format!("a/{original_path}").as_str(),
+                format!("b/{new_path}").as_str(),
+            )
+            .to_string()
+            .as_str();
+    }
+
+    buf
+}
+
+/// Diffs always use forward slashes and never start with `./`. Absolute paths are made relative to the working directory,
+/// which is where `git apply` resolves them.
+fn to_diff_path(path: &Path) -> String {
+    let path = if path.is_absolute() {
+        env::current_dir()
+            .into_iter()
+            .flat_map(|directory| [fs::canonicalize(&directory).ok(), Some(directory)])
+            .flatten()
+            .find_map(|directory| path.strip_prefix(directory).ok())
+            .unwrap_or(path)
+    } else {
+        path
+    };
+
+    let path = path.to_string_lossy().replace('\\', "/");
+    match path.strip_prefix("./") {
+        Some(stripped) => stripped.to_string(),
+        None => path,
+    }
+}
@@ -0,0 +1,58 @@
+use std::io;
+use std::ops::Range;
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+use crate::config::ConfigError;
+use crate::validation::SyntaxError;
+
+#[derive(Debug, Error)]
+pub enum TypeRsError {
+    /// The syntax tree doesn't have the shape we expect, usually because rslint didn't understand the input
+    #[error("unexpected syntax tree: {message} (at {span:?})")]
+    UnexpectedSyntax { message: String, span: Range<usize> },
+    /// The converted source has syntax errors which the original didn't have, so it must not be written
+    #[error("the conversion introduced syntax errors: {}", format_errors(.errors))]
+    InvalidOutput { errors: Vec<SyntaxError> },
+    #[error("unable to read {path:?}: {source}")]
+    Read { path: PathBuf, source: io::Error },
+    #[error("unable to write {path:?}: {source}")]
+    Write { path: PathBuf, source: io::Error },
+    #[error("unable to remove {path:?}: {source}")]
+    Remove { path: PathBuf, source: io::Error },
+    #[error("invalid journal {path:?}: {message}")]
+    InvalidJournal { path: PathBuf, message: String },
+    #[error("unable to restore {path:?}: {source}")]
+    Rollback { path: PathBuf, source: io::Error },
+    #[error("git failed: {0}")]
+    Git(io::Error),
+    #[error("unable to watch for changes: {0}")]
+    Watch(#[from] notify::Error),
+    #[error("language server failed: {0}")]
+    LanguageServer(String),
+    #[error("invalid glob pattern: {0}")]
+    Glob(#[from] globset::Error),
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+}
+
+impl TypeRsError {
+    pub(crate) fn unexpected_syntax(
+        message: impl ToString,
+        range: rslint_parser::TextRange,
+    ) -> TypeRsError {
+        TypeRsError::UnexpectedSyntax {
+            message: message.to_string(),
+            span: range.start().into()..range.end().into(),
+        }
+    }
+}
+
+fn format_errors(errors: &[SyntaxError]) -> String {
+    errors
+        .iter()
+        .map(SyntaxError::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
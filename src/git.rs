@@ -0,0 +1,75 @@
+use std::ffi::OsStr;
+use std::io::{self, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use log::debug;
+
+use crate::error::TypeRsError;
+
+/// Renames a tracked file through `git mv` so git records it as a rename rather than a delete and an add
+pub fn move_file(repository: &Path, from: &Path, to: &Path) -> Result<(), TypeRsError> {
+    run(
+        repository,
+        [OsStr::new("mv"), from.as_os_str(), to.as_os_str()],
+        &[],
+    )
+}
+
+/// Commits the working tree state of `paths`, leaving anything else that was staged untouched.
+/// The paths are passed on stdin since a large tree easily exceeds the argument limit.
+pub fn commit<P: AsRef<Path>>(
+    repository: &Path,
+    message: &str,
+    paths: &[P],
+) -> Result<(), TypeRsError> {
+    let mut pathspecs = vec![];
+    for path in paths {
+        pathspecs.extend_from_slice(path.as_ref().as_os_str().as_encoded_bytes());
+        pathspecs.push(0);
+    }
+
+    run(
+        repository,
+        [
+            "commit",
+            "--quiet",
+            "-m",
+            message,
+            "--pathspec-from-file=-",
+            "--pathspec-file-nul",
+        ],
+        &pathspecs,
+    )
+}
+
+fn run<I, S>(repository: &Path, args: I, stdin: &[u8]) -> Result<(), TypeRsError>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    let mut command = Command::new("git");
+    command
+        .arg("-C")
+        .arg(repository)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    debug!("Running {command:?}");
+
+    let mut child = command.spawn().map_err(TypeRsError::Git)?;
+    // Dropping the handle closes stdin so git stops reading
+    if let Some(mut input) = child.stdin.take() {
+        input.write_all(stdin).map_err(TypeRsError::Git)?;
+    }
+
+    let output = child.wait_with_output().map_err(TypeRsError::Git)?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(TypeRsError::Git(io::Error::other(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        )))
+    }
+}
@@ -0,0 +1,265 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, ErrorKind, Write};
+use std::path::{self, Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::error::TypeRsError;
+
+/// Holds the journal of each run along with backups of the files it replaced
+pub const JOURNAL_DIRECTORY: &str = ".type-rs-journal";
+
+/// A single change to the tree. Entries are recorded before the change is made,
+/// so a journal of a run that died halfway still describes everything that might have happened.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum JournalEntry {
+    /// `path` didn't exist before the run
+    CreateFile {
+        path: PathBuf,
+    },
+    CreateDirectory {
+        path: PathBuf,
+    },
+    /// `path` was overwritten or removed, its previous contents are stored in `backup`
+    ReplaceFile {
+        path: PathBuf,
+        backup: PathBuf,
+    },
+}
+
+impl JournalEntry {
+    pub fn path(&self) -> &Path {
+        match self {
+            JournalEntry::CreateFile { path }
+            | JournalEntry::CreateDirectory { path }
+            | JournalEntry::ReplaceFile { path, .. } => path,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct JournalState {
+    /// Opened when the first change is recorded so runs that don't change anything don't leave a journal behind
+    file: Option<File>,
+    backups: usize,
+}
+
+/// Records every file a run writes or removes so `rollback` can restore the previous tree.
+/// All writes go through a temporary file followed by a rename so no file is ever left half written.
+#[derive(Debug)]
+pub struct Journal {
+    path: PathBuf,
+    state: Mutex<JournalState>,
+}
+
+impl Journal {
+    pub fn new(path: &Path) -> Journal {
+        Journal {
+            path: path.to_path_buf(),
+            state: Mutex::new(JournalState {
+                file: None,
+                backups: 0,
+            }),
+        }
+    }
+
+    /// A new journal inside [`JOURNAL_DIRECTORY`] of `directory`, named after the current time
+    pub fn in_directory(directory: &Path) -> Journal {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+
+        Journal::new(
+            &directory
+                .join(JOURNAL_DIRECTORY)
+                .join(format!("{timestamp}.jsonl")),
+        )
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Whether any change has been recorded
+    pub fn is_used(&self) -> bool {
+        self.state.lock().unwrap().file.is_some()
+    }
+
+    pub fn write_file(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        let path = path::absolute(path)?;
+        self.record_replacement(&path)?;
+        write_atomic(&path, contents)
+    }
+
+    pub fn copy_file(&self, from: &Path, to: &Path) -> io::Result<()> {
+        let contents = fs::read(from)?;
+        self.write_file(to, &contents)
+    }
+
+    pub fn remove_file(&self, path: &Path) -> io::Result<()> {
+        let path = path::absolute(path)?;
+        self.record_replacement(&path)?;
+        fs::remove_file(path)
+    }
+
+    /// Creates `path` and any missing parents
+    pub fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+        let path = path::absolute(path)?;
+        let mut missing: Vec<&Path> = path
+            .ancestors()
+            .take_while(|ancestor| !ancestor.exists())
+            .collect();
+        missing.reverse();
+
+        for directory in missing {
+            self.record(&JournalEntry::CreateDirectory {
+                path: directory.to_path_buf(),
+            })?;
+        }
+
+        fs::create_dir_all(path)
+    }
+
+    fn record_replacement(&self, path: &Path) -> io::Result<()> {
+        if !path.exists() {
+            return self.record(&JournalEntry::CreateFile {
+                path: path.to_path_buf(),
+            });
+        }
+
+        let backup = {
+            let mut state = self.state.lock().unwrap();
+            state.backups += 1;
+            self.backup_directory()?.join(state.backups.to_string())
+        };
+        fs::create_dir_all(self.backup_directory()?)?;
+        write_atomic(&backup, &fs::read(path)?)?;
+
+        self.record(&JournalEntry::ReplaceFile {
+            path: path.to_path_buf(),
+            backup,
+        })
+    }
+
+    /// Backups of a journal `1234.jsonl` live in the sibling directory `1234`
+    fn backup_directory(&self) -> io::Result<PathBuf> {
+        Ok(path::absolute(&self.path)?.with_extension(""))
+    }
+
+    fn record(&self, entry: &JournalEntry) -> io::Result<()> {
+        debug!("Recording {entry:?}");
+        let mut line = serde_json::to_string(entry)?;
+        line.push('\n');
+
+        let mut state = self.state.lock().unwrap();
+        if state.file.is_none() {
+            if let Some(parent) = self.path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+
+            state.file = Some(
+                OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&self.path)?,
+            );
+        }
+
+        let file = state.file.as_mut().unwrap();
+        file.write_all(line.as_bytes())?;
+        file.sync_data()
+    }
+}
+
+/// Writes `contents` to a temporary file next to `path` and renames it over `path`
+pub fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let temporary = path.with_file_name(format!(".{file_name}.type-rs-{}", std::process::id()));
+
+    let result = File::create(&temporary).and_then(|mut file| {
+        file.write_all(contents)?;
+        file.sync_all()
+    });
+    if let Err(err) = result.and_then(|_| fs::rename(&temporary, path)) {
+        let _ = fs::remove_file(&temporary);
+        return Err(err);
+    }
+
+    Ok(())
+}
+
+/// Reads the entries of a journal. A truncated last line, left behind by a run that died while recording it, is ignored.
+pub fn read_journal(path: &Path) -> Result<Vec<JournalEntry>, TypeRsError> {
+    let file = File::open(path).map_err(|source| TypeRsError::Read {
+        path: path.to_path_buf(),
+        source,
+    })?;
+
+    let lines = BufReader::new(file)
+        .lines()
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|source| TypeRsError::Read {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+    let mut entries = vec![];
+    for (index, line) in lines.iter().enumerate() {
+        match serde_json::from_str(line) {
+            Ok(entry) => entries.push(entry),
+            Err(_) if index == lines.len() - 1 => {
+                warn!("Ignoring the incomplete last entry of {path:?}")
+            }
+            Err(err) => {
+                return Err(TypeRsError::InvalidJournal {
+                    path: path.to_path_buf(),
+                    message: format!("line {}: {err}", index + 1),
+                })
+            }
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Undoes the changes recorded in a journal, newest first. Returns the number of entries that were rolled back.
+pub fn rollback(path: &Path) -> Result<usize, TypeRsError> {
+    let entries = read_journal(path)?;
+    for entry in entries.iter().rev() {
+        debug!("Rolling back {entry:?}");
+        let result = match entry {
+            JournalEntry::CreateFile { path } => ignore_missing(fs::remove_file(path)),
+            JournalEntry::CreateDirectory { path } => match fs::remove_dir(path) {
+                // Leave behind anything that was added after the run, including the journal itself
+                Err(err) if err.kind() == ErrorKind::DirectoryNotEmpty => {
+                    warn!("Keeping {path:?} because it isn't empty");
+                    Ok(())
+                }
+                result => ignore_missing(result),
+            },
+            JournalEntry::ReplaceFile { path, backup } => {
+                fs::read(backup).and_then(|contents| write_atomic(path, &contents))
+            }
+        };
+
+        result.map_err(|source| TypeRsError::Rollback {
+            path: entry.path().to_path_buf(),
+            source,
+        })?;
+    }
+
+    Ok(entries.len())
+}
+
+/// The change might never have been made if the run died right after recording it
+fn ignore_missing(result: io::Result<()>) -> io::Result<()> {
+    match result {
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
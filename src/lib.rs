@@ -1,4 +1,18 @@
+pub mod cache;
+pub mod config;
+pub mod converter;
+pub mod diff;
+pub mod error;
+pub mod git;
+pub mod journal;
+pub mod lsp;
 pub mod options;
 pub mod parser;
+pub mod pool;
+pub mod report;
+pub mod stats;
 pub mod text_editor;
+pub mod traversal;
 pub mod type_definition;
+pub mod validation;
+pub mod watch;
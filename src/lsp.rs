@@ -0,0 +1,344 @@
+use std::collections::HashMap;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+
+use log::{debug, info, warn};
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, Notification as _,
+};
+use lsp_types::request::{CodeActionRequest, Request as _};
+use lsp_types::{
+    CodeAction, CodeActionKind, CodeActionOptions, CodeActionOrCommand, CodeActionParams,
+    CodeActionProviderCapability, DidChangeTextDocumentParams, DidCloseTextDocumentParams,
+    DidOpenTextDocumentParams, DocumentChangeOperation, DocumentChanges, OneOf,
+    OptionalVersionedTextDocumentIdentifier, Position, Range, RenameFile, ResourceOp,
+    ServerCapabilities, TextDocumentEdit, TextDocumentSyncCapability, TextDocumentSyncKind,
+    TextEdit, Url, WorkspaceEdit,
+};
+
+use crate::config::ConfigResolver;
+use crate::error::TypeRsError;
+use crate::parser::{suggest_types, ConversionOptions, Suggestion};
+use crate::pool::panic_message;
+use crate::text_editor::Change;
+use crate::validation::validate_output;
+
+/// Serves code actions over stdin and stdout until the client shuts the server down
+pub fn run_server() -> Result<(), TypeRsError> {
+    let (connection, io_threads) = Connection::stdio();
+
+    let capabilities = ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        code_action_provider: Some(CodeActionProviderCapability::Options(CodeActionOptions {
+            code_action_kinds: Some(vec![CodeActionKind::QUICKFIX, CodeActionKind::SOURCE]),
+            ..Default::default()
+        })),
+        ..Default::default()
+    };
+    let capabilities = serde_json::to_value(capabilities).map_err(language_server_error)?;
+    connection
+        .initialize(capabilities)
+        .map_err(language_server_error)?;
+    info!("Language server initialized");
+
+    Server::default().serve(&connection)?;
+
+    // The writer thread only finishes once every sender is gone
+    drop(connection);
+    io_threads.join().map_err(language_server_error)
+}
+
+fn language_server_error(err: impl ToString) -> TypeRsError {
+    TypeRsError::LanguageServer(err.to_string())
+}
+
+#[derive(Default)]
+struct Server {
+    documents: HashMap<Url, String>,
+    configs: ConfigResolver,
+}
+
+impl Server {
+    fn serve(&mut self, connection: &Connection) -> Result<(), TypeRsError> {
+        for message in &connection.receiver {
+            match message {
+                Message::Request(request) => {
+                    if connection
+                        .handle_shutdown(&request)
+                        .map_err(language_server_error)?
+                    {
+                        return Ok(());
+                    }
+
+                    let response = self.handle_request(request);
+                    connection
+                        .sender
+                        .send(Message::Response(response))
+                        .map_err(language_server_error)?;
+                }
+                Message::Notification(notification) => self.handle_notification(notification),
+                Message::Response(_) => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    fn handle_request(&self, request: Request) -> Response {
+        if request.method != CodeActionRequest::METHOD {
+            return Response::new_err(
+                request.id,
+                ErrorCode::MethodNotFound as i32,
+                format!("unsupported request {}", request.method),
+            );
+        }
+
+        let params: CodeActionParams = match serde_json::from_value(request.params) {
+            Ok(params) => params,
+            Err(err) => {
+                return Response::new_err(
+                    request.id,
+                    ErrorCode::InvalidParams as i32,
+                    err.to_string(),
+                )
+            }
+        };
+
+        let uri = params.text_document.uri;
+        let actions = match self.documents.get(&uri) {
+            Some(text) => code_actions(&uri, text, params.range, &self.conversion_options(&uri)),
+            None => vec![],
+        };
+
+        Response::new_ok(request.id, actions)
+    }
+
+    fn handle_notification(&mut self, notification: Notification) {
+        let method = notification.method.as_str();
+        let result = match method {
+            DidOpenTextDocument::METHOD => serde_json::from_value(notification.params).map(
+                |params: DidOpenTextDocumentParams| {
+                    self.documents
+                        .insert(params.text_document.uri, params.text_document.text);
+                },
+            ),
+            // We only ask for full document syncs so the last change holds the whole text
+            DidChangeTextDocument::METHOD => serde_json::from_value(notification.params).map(
+                |params: DidChangeTextDocumentParams| {
+                    if let Some(change) = params.content_changes.into_iter().last() {
+                        self.documents.insert(params.text_document.uri, change.text);
+                    }
+                },
+            ),
+            DidCloseTextDocument::METHOD => serde_json::from_value(notification.params).map(
+                |params: DidCloseTextDocumentParams| {
+                    self.documents.remove(&params.text_document.uri);
+                },
+            ),
+            _ => Ok(()),
+        };
+
+        if let Err(err) = result {
+            warn!("Invalid {method} notification: {err}");
+        }
+    }
+
+    /// Documents follow the configuration of the directory they live in, like files converted on the command line
+    fn conversion_options(&self, uri: &Url) -> ConversionOptions {
+        let directory = uri
+            .to_file_path()
+            .ok()
+            .and_then(|path| path.parent().map(Path::to_path_buf))
+            .unwrap_or_else(|| PathBuf::from("."));
+
+        match self.configs.resolve(&directory) {
+            Ok(config) => config.conversion_options(),
+            Err(err) => {
+                warn!("Using the default configuration for {uri}: {err}");
+                ConversionOptions::default()
+            }
+        }
+    }
+}
+
+/// One quick fix per suggestion that overlaps `range`, followed by an action that converts the whole file
+pub fn code_actions(
+    uri: &Url,
+    text: &str,
+    range: Range,
+    options: &ConversionOptions,
+) -> Vec<CodeActionOrCommand> {
+    // rslint panics on some input, which mustn't take down the whole server
+    let suggestions = match panic::catch_unwind(AssertUnwindSafe(|| suggest_types(text, options))) {
+        Ok(Ok(suggestions)) => suggestions,
+        Ok(Err(err)) => {
+            debug!("No code actions for {uri}: {err}");
+            return vec![];
+        }
+        Err(panic) => {
+            warn!(
+                "No code actions for {uri}, parsing it panicked: {}",
+                panic_message(panic)
+            );
+            return vec![];
+        }
+    };
+    if suggestions.is_empty() {
+        return vec![];
+    }
+
+    let start = position_to_offset(text, range.start);
+    let end = position_to_offset(text, range.end);
+    let mut actions: Vec<CodeActionOrCommand> = suggestions
+        .iter()
+        .filter(|suggestion| suggestion.span.start <= end && start <= suggestion.span.end)
+        .map(|suggestion| {
+            CodeActionOrCommand::CodeAction(CodeAction {
+                title: suggestion.title.clone(),
+                kind: Some(CodeActionKind::QUICKFIX),
+                edit: Some(WorkspaceEdit {
+                    changes: Some(HashMap::from([(
+                        uri.clone(),
+                        to_text_edits(text, &suggestion.changes),
+                    )])),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+        })
+        .collect();
+
+    if let Some(action) = convert_file_action(uri, text, &suggestions) {
+        actions.push(CodeActionOrCommand::CodeAction(action));
+    }
+
+    actions
+}
+
+/// Applies every suggestion and renames `.js` and `.jsx` files like a regular conversion would.
+/// `None` if the result has syntax errors the original didn't have, which the command line would refuse to write as well.
+fn convert_file_action(uri: &Url, text: &str, suggestions: &[Suggestion]) -> Option<CodeAction> {
+    let changes: Vec<Change> = suggestions
+        .iter()
+        .flat_map(|suggestion| suggestion.changes.iter().cloned())
+        .collect();
+    if let Err(err) = validate_output(text, &apply_changes(text, &changes)) {
+        debug!("Not offering to convert {uri}: {err}");
+        return None;
+    }
+
+    let mut edits = to_text_edits(text, &changes);
+    // Interfaces are all inserted at the start of the file so they have to keep the order they were created in
+    edits.sort_by_key(|edit| (edit.range.start.line, edit.range.start.character));
+
+    let mut operations = vec![DocumentChangeOperation::Edit(TextDocumentEdit {
+        text_document: OptionalVersionedTextDocumentIdentifier {
+            uri: uri.clone(),
+            version: None,
+        },
+        edits: edits.into_iter().map(OneOf::Left).collect(),
+    })];
+    if let Some(new_uri) = typescript_uri(uri, text) {
+        operations.push(DocumentChangeOperation::Op(ResourceOp::Rename(
+            RenameFile {
+                old_uri: uri.clone(),
+                new_uri,
+                options: None,
+                annotation_id: None,
+            },
+        )));
+    }
+
+    Some(CodeAction {
+        title: String::from("Convert file to TypeScript"),
+        kind: Some(CodeActionKind::SOURCE),
+        edit: Some(WorkspaceEdit {
+            document_changes: Some(DocumentChanges::Operations(operations)),
+            ..Default::default()
+        }),
+        ..Default::default()
+    })
+}
+
+/// Inserts the changes like `TextEditor::apply` does, keeping the order of insertions at the same offset
+fn apply_changes(text: &str, changes: &[Change]) -> String {
+    let mut changes = changes.to_vec();
+    changes.sort_by_key(|change| change.offset);
+
+    let mut buf = String::with_capacity(
+        text.len()
+            + changes
+                .iter()
+                .map(|change| change.text.len())
+                .sum::<usize>(),
+    );
+    let mut pointer = 0;
+    for change in &changes {
+        buf.push_str(&text[pointer..change.offset]);
+        buf.push_str(&change.text);
+        pointer = change.offset;
+    }
+    buf.push_str(&text[pointer..]);
+
+    buf
+}
+
+fn typescript_uri(uri: &Url, text: &str) -> Option<Url> {
+    let path = uri.path();
+    let (stem, extension) = path.rsplit_once('.')?;
+    let extension = match extension {
+        "jsx" => "tsx",
+        "js" if text.contains("import React") => "tsx",
+        "js" => "ts",
+        _ => return None,
+    };
+
+    let mut new_uri = uri.clone();
+    new_uri.set_path(&format!("{stem}.{extension}"));
+    Some(new_uri)
+}
+
+fn to_text_edits(text: &str, changes: &[Change]) -> Vec<TextEdit> {
+    changes
+        .iter()
+        .map(|change| {
+            let position = offset_to_position(text, change.offset);
+            TextEdit {
+                range: Range::new(position, position),
+                new_text: change.text.clone(),
+            }
+        })
+        .collect()
+}
+
+/// LSP positions count UTF-16 code units rather than bytes
+pub fn offset_to_position(text: &str, offset: usize) -> Position {
+    let before = &text[..offset.min(text.len())];
+    let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+
+    Position::new(
+        before.matches('\n').count() as u32,
+        before[line_start..].encode_utf16().count() as u32,
+    )
+}
+
+pub fn position_to_offset(text: &str, position: Position) -> usize {
+    let mut line_start = 0;
+    for _ in 0..position.line {
+        match text[line_start..].find('\n') {
+            Some(index) => line_start += index + 1,
+            None => return text.len(),
+        }
+    }
+
+    let mut units = 0;
+    for (index, character) in text[line_start..].char_indices() {
+        if units >= position.character as usize || character == '\n' {
+            return line_start + index;
+        }
+        units += character.len_utf16();
+    }
+
+    text.len()
+}
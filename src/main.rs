@@ -1,9 +1,54 @@
 use log::{debug, error, info, warn};
-use std::path::PathBuf;
-use std::{fs, thread};
+use std::collections::HashSet;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use std::{env, fs, process};
+use structopt::clap::{Error as ClapError, ErrorKind};
 use structopt::StructOpt;
-use type_rs::options::Options;
-use type_rs::parser::add_types;
+use type_rs::cache::Cache;
+use type_rs::config::{ConfigResolver, OutputFormat};
+use type_rs::converter::{Converter, JsxHandling};
+use type_rs::diff::create_unified_diff;
+use type_rs::error::TypeRsError;
+use type_rs::git;
+use type_rs::journal::{self, Journal};
+use type_rs::lsp;
+use type_rs::options::{Command, Options, STDIN_PATH};
+use type_rs::parser::ConversionSummary;
+use type_rs::pool::{default_jobs, process_all};
+use type_rs::report::{write_report, FileReport, FileStatus};
+use type_rs::stats::collect_stats;
+use type_rs::traversal::{find_all_files, find_source_files, FileFilter, SourceFile};
+use type_rs::watch::watch_source_files;
+
+struct Conversion {
+    path: PathBuf,
+    new_path: PathBuf,
+    contents: String,
+    new_source: String,
+    summary: ConversionSummary,
+    elapsed: Duration,
+}
+
+impl Conversion {
+    fn report(&self) -> FileReport {
+        let status = if self.new_source == self.contents {
+            FileStatus::Unchanged
+        } else {
+            FileStatus::Converted
+        };
+
+        FileReport::converted(
+            &self.path,
+            &self.new_path,
+            status,
+            &self.summary,
+            self.elapsed,
+        )
+    }
+}
 
 fn main() {
     env_logger::init_from_env(
@@ -11,76 +56,569 @@ fn main() {
     );
 
     let args = Options::from_args();
-    info!("Starting now at {:?}", &args.path);
+    let result = match &args.command {
+        Some(Command::Rollback { journal }) => journal::rollback(journal)
+            .map(|entries| info!("Rolled back {entries} changes recorded in {journal:?}")),
+        Some(Command::Watch { .. }) if args.git => ClapError::with_description(
+            "--git can't be used while watching",
+            ErrorKind::ArgumentConflict,
+        )
+        .exit(),
+        Some(Command::Watch { .. }) => watch(&args),
+        Some(Command::Lsp) => lsp::run_server(),
+        Some(Command::Stats { json, .. }) => stats(&args, *json),
+        None => {
+            if let Err(err) = args.validate() {
+                err.exit();
+            }
+            info!("Starting now at {:?}", args.path());
+            run(&args)
+        }
+    };
+
+    if let Err(err) = result {
+        error!("{err}");
+        process::exit(1);
+    }
+}
+
+fn run(args: &Options) -> Result<(), TypeRsError> {
+    if args.is_stdin() {
+        return convert_stdin(args);
+    }
+
+    let filter = FileFilter::new(&args.include, &args.exclude)?.with_typescript(args.typescript);
+
+    // Fail early on a broken configuration rather than skipping the entire tree
+    let configs = Arc::new(ConfigResolver::new().with_overrides(args.config_overrides()));
+    configs.resolve(root_directory(args))?;
 
-    traverse_directories(args.path);
+    let traversal = find_source_files(args.path(), &filter, &configs);
+    for file in &traversal.files {
+        info!("Processing {:?}", file.path);
+    }
+    let (journal, cache) = journal_and_cache(args);
+    let files = traversal.files;
+    let jobs = args.jobs.unwrap_or_else(default_jobs);
+    debug!("Processing {} files with {jobs} workers", files.len());
+
+    let paths: Vec<PathBuf> = files.iter().map(|file| file.path.clone()).collect();
+    let mut reports = vec![];
+    if args.git {
+        let mut conversions = vec![];
+        for (result, path) in process_all(files, jobs, |file| convert(&file, cache.as_ref()))
+            .into_iter()
+            .zip(&paths)
+        {
+            match result {
+                Ok(Ok(Some(conversion))) => conversions.push(conversion),
+                Ok(Ok(None)) => reports.push(FileReport::new(path, FileStatus::SkippedFlow)),
+                Ok(Err(err)) => reports.push(report_error(path, err)),
+                Err(panic) => reports.push(report_panic(path, panic)),
+            }
+        }
+
+        reports.extend(convert_with_git(conversions, args)?);
+    } else {
+        reports.extend(convert_files(files, args, &journal, cache.as_ref()));
+    }
+
+    if let Some(out_dir) = args.out_dir.as_deref().filter(|_| args.copy_untouched) {
+        let written: HashSet<&Path> = reports
+            .iter()
+            .filter(|report| report.target.is_some())
+            .map(|report| report.source.as_path())
+            .collect();
+        // Walk again rather than reuse the traversal, which skipped excluded directories and ignored files
+        let untouched = find_all_files(args.path(), out_dir)
+            .into_iter()
+            .filter(|path| !written.contains(path.as_path()));
+
+        for path in untouched {
+            let target = mirror_path(args, out_dir, &path);
+            debug!("Copying {path:?} to {target:?}");
+            create_parent_directory(&journal, &target)?;
+            journal
+                .copy_file(&path, &target)
+                .map_err(|source| TypeRsError::Write {
+                    path: target.clone(),
+                    source,
+                })?;
+        }
+    }
+
+    if journal.is_used() {
+        info!(
+            "Recorded the changes in {:?}, run `type-rs rollback {:?}` to undo them",
+            journal.path(),
+            journal.path()
+        );
+    }
+
+    let count = |status| {
+        reports
+            .iter()
+            .filter(|report| report.status == status)
+            .count()
+    };
+    info!(
+        "Finished conversion! {} converted, {} unchanged, {} skipped due to Flow, {} failed",
+        count(FileStatus::Converted),
+        count(FileStatus::Unchanged),
+        count(FileStatus::SkippedFlow),
+        reports
+            .iter()
+            .filter(|report| report.status.is_failure())
+            .count()
+    );
 
-    info!("Finished conversion!")
+    if let Some(report_path) = &args.report {
+        reports.extend(
+            traversal
+                .excluded
+                .iter()
+                .map(|path| FileReport::new(path, FileStatus::Excluded)),
+        );
+
+        write_report(report_path, &reports).map_err(|source| TypeRsError::Write {
+            path: report_path.clone(),
+            source,
+        })?;
+    }
+
+    Ok(())
 }
 
-fn traverse_directories(path: PathBuf) {
-    // We use metadata since path::is_file() coerces an error into false
-    let metadata = match fs::metadata(path.clone()) {
-        Ok(m) => m,
-        Err(err) => {
-            warn!("Unable to read the metadata for {:?}: {}", path, err);
-            return;
+/// Converts files as they're added or modified until the process is stopped
+fn watch(args: &Options) -> Result<(), TypeRsError> {
+    let filter = FileFilter::new(&args.include, &args.exclude)?;
+    let configs = Arc::new(ConfigResolver::new().with_overrides(args.config_overrides()));
+    configs.resolve(root_directory(args))?;
+
+    let (journal, cache) = journal_and_cache(args);
+    info!(
+        "Watching {:?} for new and modified JavaScript files",
+        args.path()
+    );
+
+    let mut announced_journal = false;
+    watch_source_files(args.path(), &filter, &configs, |files| {
+        for report in convert_files(files, args, &journal, cache.as_ref()) {
+            if let Some(target) = report
+                .target
+                .as_ref()
+                .filter(|_| !report.status.is_failure())
+            {
+                info!("Converted {:?} to {target:?}", report.source);
+            }
         }
+
+        if journal.is_used() && !announced_journal {
+            info!("Recording the changes in {:?}", journal.path());
+            announced_journal = true;
+        }
+    })
+}
+
+fn stats(args: &Options, json: bool) -> Result<(), TypeRsError> {
+    let filter = FileFilter::new(&args.include, &args.exclude)?;
+    let configs = Arc::new(ConfigResolver::new().with_overrides(args.config_overrides()));
+    configs.resolve(root_directory(args))?;
+
+    let jobs = args.jobs.unwrap_or_else(default_jobs);
+    let stats = collect_stats(args.path(), &filter, &configs, jobs);
+    let output = if json {
+        serde_json::to_string_pretty(&stats).expect("stats always serialize") + "\n"
+    } else {
+        stats.to_table()
+    };
+
+    io::stdout()
+        .write_all(output.as_bytes())
+        .map_err(|source| TypeRsError::Write {
+            path: PathBuf::from(STDIN_PATH),
+            source,
+        })
+}
+
+/// The journal and cache live in the output directory, if any, to keep our own files out of a read-only input tree
+fn journal_and_cache(args: &Options) -> (Journal, Option<Cache>) {
+    let state_directory = args.out_dir.as_deref().unwrap_or(root_directory(args));
+    let journal = match &args.journal {
+        Some(path) => Journal::new(path),
+        None => Journal::in_directory(state_directory),
+    };
+    let cache = (!args.no_cache).then(|| Cache::in_directory(state_directory));
+
+    (journal, cache)
+}
+
+/// Runs `handle_file` for every file on the worker pool
+fn convert_files(
+    files: Vec<SourceFile>,
+    args: &Options,
+    journal: &Journal,
+    cache: Option<&Cache>,
+) -> Vec<FileReport> {
+    let jobs = args.jobs.unwrap_or_else(default_jobs);
+    let paths: Vec<PathBuf> = files.iter().map(|file| file.path.clone()).collect();
+
+    process_all(files, jobs, |file| handle_file(file, args, journal, cache))
+        .into_iter()
+        .zip(&paths)
+        .map(|(result, path)| result.unwrap_or_else(|panic| report_panic(path, panic)))
+        .collect()
+}
+
+fn root_directory(options: &Options) -> &Path {
+    let path = options.path();
+    if path.is_dir() {
+        path
+    } else {
+        path.parent().unwrap_or_else(|| Path::new("."))
+    }
+}
+
+/// Converts a single file from stdin so editors, hooks and pipelines can use type-rs as a filter.
+/// Nothing is written to stdout if the conversion fails.
+fn convert_stdin(args: &Options) -> Result<(), TypeRsError> {
+    let mut contents = String::new();
+    io::stdin()
+        .read_to_string(&mut contents)
+        .map_err(|source| TypeRsError::Read {
+            path: PathBuf::from(STDIN_PATH),
+            source,
+        })?;
+
+    let path = args
+        .filename
+        .clone()
+        .unwrap_or_else(|| PathBuf::from("stdin.js"));
+    let directory = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let config = ConfigResolver::new()
+        .with_overrides(args.config_overrides())
+        .resolve(directory)?;
+
+    let converter = Converter::default()
+        .with_options(config.conversion_options())
+        .with_jsx(args.stdin_jsx_handling());
+    let new_path = path.with_extension(converter.target_extension(&contents));
+    debug!("Converting stdin as {new_path:?}");
+
+    let new_source = if contents.contains("@flow") {
+        warn!("Passing stdin through unchanged due to Flow");
+        contents.clone()
+    } else {
+        converter.convert(&contents)?.source
     };
 
-    if let Some(full_path) = path.as_os_str().to_str() {
-        if full_path.contains("node_modules") {
-            return;
+    let output = if args.diff {
+        create_unified_diff(&path, &new_path, &contents, &new_source)
+    } else {
+        new_source
+    };
+
+    io::stdout()
+        .write_all(output.as_bytes())
+        .map_err(|source| TypeRsError::Write {
+            path: PathBuf::from(STDIN_PATH),
+            source,
+        })
+}
+
+/// Where `path` ends up when the input tree is mirrored into `out_dir`
+fn mirror_path(options: &Options, out_dir: &Path, path: &Path) -> PathBuf {
+    match path.strip_prefix(root_directory(options)) {
+        Ok(relative) => out_dir.join(relative),
+        Err(_) => out_dir.join(path.file_name().unwrap_or_default()),
+    }
+}
+
+fn create_parent_directory(journal: &Journal, path: &Path) -> Result<(), TypeRsError> {
+    match path.parent() {
+        Some(parent) => journal
+            .create_dir_all(parent)
+            .map_err(|source| TypeRsError::Write {
+                path: parent.to_path_buf(),
+                source,
+            }),
+        None => Ok(()),
+    }
+}
+
+fn report_error(path: &Path, err: TypeRsError) -> FileReport {
+    error!("Conversion of {path:?} failed: {err}");
+    FileReport::failed(path, &err)
+}
+
+/// Every file that should have been part of a failed commit failed with it
+fn report_commit_error<'a>(
+    conversions: impl IntoIterator<Item = &'a Conversion>,
+    err: &TypeRsError,
+) -> Vec<FileReport> {
+    let message = match err {
+        TypeRsError::Git(source) => source.to_string(),
+        err => err.to_string(),
+    };
+
+    conversions
+        .into_iter()
+        .map(|conversion| {
+            report_error(
+                &conversion.path,
+                TypeRsError::Git(io::Error::other(message.clone())),
+            )
+        })
+        .collect()
+}
+
+fn report_panic(path: &Path, message: String) -> FileReport {
+    error!("Conversion of {path:?} panicked: {message}");
+    FileReport {
+        error: Some(message),
+        ..FileReport::new(path, FileStatus::Panicked)
+    }
+}
+
+/// Returns `None` for files we skip on purpose
+fn convert(file: &SourceFile, cache: Option<&Cache>) -> Result<Option<Conversion>, TypeRsError> {
+    let SourceFile {
+        path,
+        file_name,
+        extension,
+        config,
+    } = file;
+
+    let start = Instant::now();
+    let contents = fs::read_to_string(path).map_err(|source| TypeRsError::Read {
+        path: path.clone(),
+        source,
+    })?;
+
+    if contents.contains("@flow") {
+        warn!("Skipped {path:?} due to Flow");
+        return Ok(None);
+    }
+
+    let converter = Converter::default()
+        .with_options(config.conversion_options())
+        .with_jsx(if *extension == "tsx" {
+            JsxHandling::Tsx
+        } else {
+            JsxHandling::Detect
+        });
+    // Files that were converted before keep their name
+    let extension = if file.is_typescript() {
+        extension
+    } else {
+        converter.target_extension(&contents)
+    };
+    let key = Cache::key(&contents, converter.options());
+    let (new_source, summary) = match cache.and_then(|cache| cache.get(&key)) {
+        Some(cached) => cached,
+        None => {
+            // Fails rather than replace the original with something that doesn't parse
+            let result = converter.convert(&contents)?;
+            if let Some(cache) = cache {
+                cache.insert(&key, &result.source, &result.summary);
+            }
+
+            (result.source, result.summary)
         }
+    };
+    let new_path = path.with_file_name(format!("{file_name}.{extension}"));
+
+    Ok(Some(Conversion {
+        path: path.clone(),
+        new_path,
+        contents,
+        new_source,
+        summary,
+        elapsed: start.elapsed(),
+    }))
+}
+
+fn handle_file(
+    file: SourceFile,
+    options: &Options,
+    journal: &Journal,
+    cache: Option<&Cache>,
+) -> FileReport {
+    // Flags passed on the command line take precedence over the configuration files
+    let output = if options.diff {
+        OutputFormat::Diff
+    } else if options.dry_run {
+        OutputFormat::DryRun
+    } else if options.out_dir.is_some() {
+        // An explicit output directory asks for files to be written
+        OutputFormat::Write
+    } else {
+        file.config.output()
+    };
+
+    // A dry run or diff shouldn't leave a cache behind in the tree it was only asked to look at
+    let cache = cache.filter(|_| output == OutputFormat::Write);
+    let mut conversion = match convert(&file, cache) {
+        Ok(Some(conversion)) => conversion,
+        Ok(None) => return FileReport::new(&file.path, FileStatus::SkippedFlow),
+        Err(err) => return report_error(&file.path, err),
+    };
+    if let Some(out_dir) = &options.out_dir {
+        conversion.new_path = mirror_path(options, out_dir, &conversion.new_path);
+    }
+
+    let report = conversion.report();
+    let Conversion {
+        path,
+        new_path,
+        contents,
+        new_source,
+        summary,
+        ..
+    } = conversion;
+
+    if output == OutputFormat::Diff {
+        print!(
+            "{}",
+            create_unified_diff(&path, &new_path, &contents, &new_source)
+        );
+        return report;
     }
 
-    if metadata.is_file() {
-        if let Some(file_name) = path.file_stem() {
-            let file_name = file_name
-                .to_os_string()
-                .into_string()
-                .expect("Failed to convert file path");
-            let target_extension = match path.extension().and_then(|ext| ext.to_str()) {
-                Some("js") => "ts",
-                Some("jsx") => "tsx",
-                _ => return,
-            };
+    if output == OutputFormat::DryRun {
+        let extension = new_path.extension().unwrap_or_default();
+        println!(
+            "{path:?} -> {new_path:?} (.{}): {} annotations, {} interfaces",
+            extension.to_string_lossy(),
+            summary.annotations(),
+            summary.interfaces.len()
+        );
+        return report;
+    }
 
-            info!("Processing {:?}", path);
+    // Re-running over a TypeScript file that needs nothing new shouldn't touch it or start a journal
+    if new_path == path && new_source == contents {
+        return report;
+    }
 
-            thread::spawn(move || handle_file(path, file_name, target_extension));
-            return;
+    debug!("Writing new file at {new_path:?}");
+    if options.out_dir.is_some() {
+        if let Err(err) = create_parent_directory(journal, &new_path) {
+            return report_error(&path, err);
         }
     }
 
-    debug!("Diving into new directory: {:?}", path);
-    for directory in fs::read_dir(path).unwrap().flatten() {
-        traverse_directories(directory.path().to_path_buf());
+    if let Err(source) = journal.write_file(&new_path, new_source.as_bytes()) {
+        return report_error(
+            &path,
+            TypeRsError::Write {
+                path: new_path,
+                source,
+            },
+        );
     }
+
+    // The originals stay where they are when writing to a separate directory or updating a TypeScript file
+    if options.out_dir.is_some() || new_path == path {
+        return report;
+    }
+
+    if let Err(source) = journal.remove_file(&path) {
+        return report_error(
+            &path,
+            TypeRsError::Remove {
+                path: path.clone(),
+                source,
+            },
+        );
+    }
+
+    report
 }
 
-fn handle_file(path: PathBuf, file_name: String, extension: &str) {
-    match fs::read_to_string(path.clone()) {
-        Ok(contents) => {
-            if contents.contains("@flow") {
-                warn!("Skipped {path:?} due to Flow");
-                return;
-            }
+/// Renames every file in a first commit and adds the type annotations in a second one.
+/// Keeping the rename separate from the edits lets `git log --follow` and blame track the history of each file.
+/// Only failures that affect every file, like being unable to commit, are returned as an error.
+fn convert_with_git(
+    conversions: Vec<Conversion>,
+    options: &Options,
+) -> Result<Vec<FileReport>, TypeRsError> {
+    let repository = root_directory(options);
+
+    // git resolves relative paths against the repository rather than our working directory
+    let working_directory = env::current_dir().map_err(|source| TypeRsError::Read {
+        path: PathBuf::from("."),
+        source,
+    })?;
 
-            let extension = if contents.contains("import React") {
-                "tsx"
-            } else {
-                extension
-            };
-
-            let new_source = add_types(contents);
-            let new_path = path.with_file_name(format!("{file_name}.{extension}"));
-            debug!("Writing new file at {new_path:?}");
-            fs::write(new_path, new_source).expect("Unable to write file");
-            fs::remove_file(path).expect("Failed to delete file");
+    let mut reports = vec![];
+    let mut renamed = vec![];
+    for mut conversion in conversions {
+        conversion.path = working_directory.join(&conversion.path);
+        conversion.new_path = working_directory.join(&conversion.new_path);
+
+        if conversion.path == conversion.new_path {
+            renamed.push(conversion);
+            continue;
+        }
+
+        match git::move_file(repository, &conversion.path, &conversion.new_path) {
+            Ok(()) => renamed.push(conversion),
+            Err(err) => reports.push(report_error(&conversion.path, err)),
         }
-        Err(error) => {
-            error!("Unable to load file {file_name}: {error}");
+    }
+
+    let renamed_paths: Vec<&Path> = renamed
+        .iter()
+        .filter(|conversion| conversion.path != conversion.new_path)
+        .flat_map(|conversion| [conversion.path.as_path(), conversion.new_path.as_path()])
+        .collect();
+    if !renamed_paths.is_empty() {
+        if let Err(err) = git::commit(
+            repository,
+            "Rename JavaScript files to TypeScript",
+            &renamed_paths,
+        ) {
+            reports.extend(report_commit_error(&renamed, &err));
+            return Ok(reports);
         }
     }
+
+    let mut changed = vec![];
+    for conversion in &renamed {
+        if conversion.new_source == conversion.contents {
+            reports.push(conversion.report());
+            continue;
+        }
+
+        debug!("Writing new file at {:?}", conversion.new_path);
+        match fs::write(&conversion.new_path, &conversion.new_source) {
+            Ok(()) => changed.push(conversion),
+            Err(source) => reports.push(report_error(
+                &conversion.path,
+                TypeRsError::Write {
+                    path: conversion.new_path.clone(),
+                    source,
+                },
+            )),
+        }
+    }
+
+    if changed.is_empty() {
+        return Ok(reports);
+    }
+
+    let changed_paths: Vec<&Path> = changed
+        .iter()
+        .map(|conversion| conversion.new_path.as_path())
+        .collect();
+    match git::commit(repository, "Add type annotations", &changed_paths) {
+        Ok(()) => reports.extend(changed.iter().map(|conversion| conversion.report())),
+        Err(err) => reports.extend(report_commit_error(changed, &err)),
+    }
+
+    Ok(reports)
 }
@@ -1,8 +1,182 @@
+use std::path::{Path, PathBuf};
+
+use structopt::clap::{AppSettings, Error as ClapError, ErrorKind};
 use structopt::StructOpt;
 
+use crate::config::{Config, FallbackType};
+use crate::converter::JsxHandling;
+
+/// Passed as the path to read a single file from stdin and write the result to stdout
+pub const STDIN_PATH: &str = "-";
+
 #[derive(Debug, StructOpt)]
+#[structopt(settings = &[AppSettings::ArgsNegateSubcommands])]
 pub struct Options {
-    /// Directory to evaluate
+    /// Directory to evaluate, or `-` to convert stdin and write the result to stdout.
+    /// Required unless a subcommand is given
     #[structopt(parse(from_os_str))]
-    pub path: std::path::PathBuf,
+    pub path: Option<PathBuf>,
+
+    /// Report the conversions that would happen without changing any files
+    #[structopt(long, global = true)]
+    pub dry_run: bool,
+
+    /// Print a unified diff of every conversion instead of changing any files
+    #[structopt(long, global = true, conflicts_with = "dry-run")]
+    pub diff: bool,
+
+    /// Rename the files with `git mv` in one commit and add the type annotations in a second commit
+    #[structopt(long, conflicts_with_all = &["dry-run", "diff"])]
+    pub git: bool,
+
+    /// Only convert files matching this glob, relative to `path`. Can be repeated
+    #[structopt(long = "include", number_of_values = 1, global = true)]
+    pub include: Vec<String>,
+
+    /// Skip files and directories matching this glob, relative to `path`. Can be repeated.
+    /// Dependencies, build output and minified files are always excluded
+    #[structopt(long = "exclude", number_of_values = 1, global = true)]
+    pub exclude: Vec<String>,
+
+    /// Also process `.ts` and `.tsx` files, only annotating what still lacks a type
+    #[structopt(long)]
+    pub typescript: bool,
+
+    /// Convert every file again instead of reusing the results stored in `.type-rs-cache`
+    #[structopt(long, global = true)]
+    pub no_cache: bool,
+
+    /// Number of files to convert in parallel. Defaults to the number of CPUs
+    #[structopt(long, short, global = true)]
+    pub jobs: Option<std::num::NonZeroUsize>,
+
+    /// Write a JSON report with the outcome of every file to this path
+    #[structopt(long, parse(from_os_str))]
+    pub report: Option<std::path::PathBuf>,
+
+    /// Write the converted files to this directory, mirroring the input tree, instead of replacing the originals
+    #[structopt(long, parse(from_os_str), global = true, conflicts_with_all = &["dry-run", "diff", "git"])]
+    pub out_dir: Option<std::path::PathBuf>,
+
+    /// Copy every file that isn't converted to the output directory as well
+    #[structopt(long, requires = "out-dir")]
+    pub copy_untouched: bool,
+
+    /// Treat stdin as JSX and output TSX
+    #[structopt(long)]
+    pub jsx: bool,
+
+    /// Name of the file passed on stdin. Its extension decides whether it contains JSX
+    /// and its directory decides which configuration applies
+    #[structopt(long, parse(from_os_str))]
+    pub filename: Option<std::path::PathBuf>,
+
+    /// Record the changes in this journal instead of a new one in `.type-rs-journal`
+    #[structopt(long, parse(from_os_str), global = true, conflicts_with = "git")]
+    pub journal: Option<std::path::PathBuf>,
+
+    /// Type to annotate code with when nothing better can be inferred: `unknown`, `any`, or `none` to leave it unannotated.
+    /// Overrides the configuration files
+    #[structopt(long, global = true, possible_values = &["unknown", "any", "none"])]
+    pub fallback_type: Option<FallbackType>,
+
+    /// Fallback type of catch clause variables, e.g. `unknown` to match `useUnknownInCatchVariables`.
+    /// Defaults to `--fallback-type`
+    #[structopt(long, global = true, possible_values = &["unknown", "any", "none"])]
+    pub catch_clause_type: Option<FallbackType>,
+
+    #[structopt(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, StructOpt)]
+pub enum Command {
+    /// Restore the files changed by a previous run
+    Rollback {
+        /// Journal written by the run, found in `.type-rs-journal` by default
+        #[structopt(name = "JOURNAL", parse(from_os_str))]
+        journal: PathBuf,
+    },
+    /// Start a language server on stdin and stdout which offers the annotations as code actions
+    Lsp,
+    /// Keep converting `.js` and `.jsx` files as they are added or modified
+    Watch {
+        /// Directory to watch
+        #[structopt(name = "PATH", parse(from_os_str))]
+        path: PathBuf,
+    },
+    /// Report how far the migration got: JavaScript and TypeScript files and how their code is annotated, per directory
+    Stats {
+        /// Directory to report on
+        #[structopt(name = "PATH", parse(from_os_str))]
+        path: PathBuf,
+        /// Print the numbers of every directory and file as JSON instead of a table
+        #[structopt(long)]
+        json: bool,
+    },
+}
+
+impl Options {
+    /// The path to convert. `main` makes sure it's present when there is no subcommand.
+    pub fn path(&self) -> &Path {
+        match (&self.command, &self.path) {
+            (Some(Command::Watch { path }), _) | (Some(Command::Stats { path, .. }), _) => path,
+            (_, Some(path)) => path,
+            _ => Path::new("."),
+        }
+    }
+
+    pub fn is_stdin(&self) -> bool {
+        self.path() == Path::new(STDIN_PATH)
+    }
+
+    /// Checks the combinations of arguments which clap can't express when converting without a subcommand
+    pub fn validate(&self) -> Result<(), ClapError> {
+        if self.path.is_none() {
+            return Err(ClapError::with_description(
+                "The following required arguments were not provided:\n    <path>",
+                ErrorKind::MissingRequiredArgument,
+            ));
+        }
+        if self.is_stdin() && (self.git || self.dry_run || self.out_dir.is_some()) {
+            return Err(ClapError::with_description(
+                "--git, --dry-run and --out-dir can't be used when reading from stdin",
+                ErrorKind::ArgumentConflict,
+            ));
+        }
+        if !self.is_stdin() && (self.jsx || self.filename.is_some()) {
+            return Err(ClapError::with_description(
+                "--jsx and --filename can only be used when reading from stdin",
+                ErrorKind::ArgumentConflict,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Whether stdin is converted to TSX, because of `--jsx` or the extension of `--filename`
+    pub fn stdin_jsx_handling(&self) -> JsxHandling {
+        let is_jsx = self.jsx
+            || matches!(
+                self.filename
+                    .as_ref()
+                    .and_then(|path| path.extension())
+                    .and_then(|ext| ext.to_str()),
+                Some("jsx") | Some("tsx")
+            );
+        if is_jsx {
+            JsxHandling::Tsx
+        } else {
+            JsxHandling::Detect
+        }
+    }
+
+    /// The settings given on the command line, which take precedence over the configuration files
+    pub fn config_overrides(&self) -> Config {
+        Config {
+            fallback_type: self.fallback_type,
+            catch_clause_type: self.catch_clause_type,
+            ..Config::default()
+        }
+    }
 }
@@ -1,24 +1,166 @@
 use std::collections::HashSet;
+use std::ops::Range;
 
 use inflector::Inflector;
 use log::{debug, trace};
 use rslint_parser::{
     ast::{
-        ArrowExpr, CatchClause, ClassDecl, Constructor, Declarator, Expr, FnDecl, FnExpr,
-        ForStmtInit, Method, Name, ParameterList, Pattern,
+        ArrayPattern, ArrowExpr, ArrowExprParams, AssignPattern, BlockStmt, BreakStmt, CatchClause,
+        ClassDecl, Constructor, Declarator, Expr, ExprOrBlock, ExprOrSpread, FnDecl, FnExpr,
+        ForStmtInit, Getter, LiteralKind, LiteralProp, Method, Name, ObjectExpr, ParameterList,
+        Pattern, PropName, ReturnStmt, Setter, Stmt, SwitchCase, TsInterfaceDecl, TsType,
+        TsTypeAliasDecl, YieldExpr,
     },
-    parse_with_syntax, AstNode, Syntax, SyntaxKind, SyntaxNode, SyntaxNodeExt,
+    parse_with_syntax, AstNode, Syntax, SyntaxKind, SyntaxNode, SyntaxNodeExt, TextRange,
 };
+use serde::{Deserialize, Serialize};
 
 use crate::{
-    text_editor::{TextEdit, TextEditor},
+    config::{FallbackType, Naming, Strictness},
+    converter::Diagnostic,
+    error::TypeRsError,
+    text_editor::{Change, TextEdit, TextEditor},
     type_definition::{
-        create_type_definition, define_type_based_on_usage, get_type_from_expression, TypeDef,
-        TypeDefinition,
+        create_type_definition, define_element_type_based_on_usage, define_type_based_on_default,
+        define_type_based_on_returns, define_type_based_on_usage, get_type_from_expression,
+        is_used_as_promise, TypeDef, TypeDefinition,
     },
 };
 
-pub fn add_types(contents: String) -> String {
+/// Tallies of what `add_types` inserted into a single file
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConversionSummary {
+    pub parameters: usize,
+    pub variables: usize,
+    pub catch_clauses: usize,
+    pub return_types: usize,
+    /// Type arguments added to React components
+    pub components: usize,
+    /// Names of the generated interfaces
+    pub interfaces: Vec<String>,
+    /// Annotations and fields of generated interfaces which fell back to `any` (or `unknown` in strict mode)
+    /// because nothing better could be inferred
+    pub any_annotations: usize,
+}
+
+impl ConversionSummary {
+    pub fn annotations(&self) -> usize {
+        self.parameters + self.variables + self.catch_clauses + self.return_types + self.components
+    }
+}
+
+/// A group of changes which belong together, e.g. a parameter annotation and the interface it refers to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suggestion {
+    pub title: String,
+    /// Byte offsets of the code the suggestion is about
+    pub span: Range<usize>,
+    pub changes: Vec<Change>,
+}
+
+impl Suggestion {
+    fn from_changes(changes: &[Change], title: String, span: TextRange) -> Option<Suggestion> {
+        if changes.is_empty() {
+            return None;
+        }
+
+        Some(Suggestion {
+            title,
+            span: span.start().into()..span.end().into(),
+            changes: changes.to_vec(),
+        })
+    }
+}
+
+/// Which kinds of code `add_types` is allowed to annotate
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct InferenceRules {
+    pub parameters: bool,
+    pub variables: bool,
+    pub catch_clauses: bool,
+    /// Annotate functions with the types they return, e.g. `: void` when nothing is returned
+    pub return_types: bool,
+    pub react_components: bool,
+    /// Generate interfaces based on how parameters are used rather than annotating them as the fallback type
+    pub interfaces: bool,
+}
+
+impl Default for InferenceRules {
+    fn default() -> Self {
+        InferenceRules {
+            parameters: true,
+            variables: true,
+            catch_clauses: true,
+            return_types: true,
+            react_components: true,
+            interfaces: true,
+        }
+    }
+}
+
+/// Everything `add_types` found in a file, before the changes are applied
+pub(crate) struct Inference {
+    pub editor: TextEditor,
+    pub summary: ConversionSummary,
+    pub suggestions: Vec<Suggestion>,
+    /// The usages behind every generated interface, named after the interface
+    pub type_definitions: Vec<TypeDefinition>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct ConversionOptions {
+    pub strictness: Strictness,
+    /// Overrides the fallback type of `strictness`
+    pub fallback_type: Option<FallbackType>,
+    /// Defaults to the fallback type of everything else
+    pub catch_clause_type: Option<FallbackType>,
+    pub naming: Naming,
+    pub rules: InferenceRules,
+}
+
+impl ConversionOptions {
+    pub fn fallback_type(&self) -> FallbackType {
+        self.fallback_type
+            .unwrap_or_else(|| self.strictness.fallback_type())
+    }
+
+    pub fn catch_clause_type(&self) -> FallbackType {
+        self.catch_clause_type
+            .unwrap_or_else(|| self.fallback_type())
+    }
+}
+
+pub fn add_types(contents: String) -> Result<String, TypeRsError> {
+    add_types_with_summary(contents).map(|(source, _)| source)
+}
+
+pub fn add_types_with_summary(
+    contents: String,
+) -> Result<(String, ConversionSummary), TypeRsError> {
+    add_types_with_options(contents, &ConversionOptions::default())
+}
+
+pub fn add_types_with_options(
+    contents: String,
+    options: &ConversionOptions,
+) -> Result<(String, ConversionSummary), TypeRsError> {
+    let mut inference = infer_types(contents, options)?;
+    Ok((inference.editor.apply(), inference.summary))
+}
+
+/// The same changes `add_types` makes, grouped by the code they apply to so they can be offered one at a time
+pub fn suggest_types(
+    contents: &str,
+    options: &ConversionOptions,
+) -> Result<Vec<Suggestion>, TypeRsError> {
+    infer_types(contents.to_string(), options).map(|inference| inference.suggestions)
+}
+
+pub(crate) fn infer_types(
+    contents: String,
+    options: &ConversionOptions,
+) -> Result<Inference, TypeRsError> {
     let syntax = Syntax::default().typescript();
     let parse = parse_with_syntax(contents.as_str(), 0, syntax);
     let ast = parse.syntax();
@@ -27,40 +169,133 @@ pub fn add_types(contents: String) -> String {
     let start_of_file = ast.text_range();
 
     let mut created_types = HashSet::new();
+    let declared_types = find_declared_types(&ast);
+    let mut summary = ConversionSummary::default();
+    let mut suggestions = vec![];
+    let mut type_definitions = vec![];
+    let mut diagnostics = vec![];
+    let fallback = options.fallback_type();
+    let fallback_type = fallback.type_name();
+    let catch_fallback = options.catch_clause_type();
+    let rules = &options.rules;
 
     for descendant in ast.descendants() {
+        let first_change = fixer.changes().len();
         match descendant.kind() {
-            SyntaxKind::PARAMETER_LIST => {
+            SyntaxKind::PARAMETER_LIST if rules.parameters => {
                 let param_list = descendant.to::<ParameterList>();
-                let outer_scope = descendant
-                    .ancestors()
-                    .find(|anc| {
-                        anc.is::<FnDecl>()
-                            || anc.is::<Constructor>()
-                            || anc.is::<Method>()
-                            || anc.is::<ArrowExpr>()
-                            || anc.is::<FnExpr>()
-                    })
-                    .unwrap();
+                let outer_scope = descendant.ancestors().find(is_function).ok_or_else(|| {
+                    TypeRsError::unexpected_syntax(
+                        "parameter list outside of a function",
+                        descendant.text_range(),
+                    )
+                })?;
                 for param in param_list.parameters() {
-                    /*  Taking the text here is very crude and means that any parameter that has a default value
-                       will end up not finding any usages. i.e. this function
-                       ```
-                       function f(a = 32)
-                       ```
-                       will result in a name of "a = 32", which doesn't correspond to any identifier.
-                       More reliable would be to match this to a SinglePattern instead except
-                       we don't actually care about these scenarios so we're okay with it for now.
-                    */
-                    let parameter_name = param.text();
-                    let new_parameter_type = parameter_name.to_pascal_case();
-                    let param_usages =
-                        define_type_based_on_usage(&outer_scope, parameter_name.as_str());
+                    if has_type_annotation(&param) {
+                        continue;
+                    }
+
+                    let first_change = fixer.changes().len();
+                    let interfaces = summary.interfaces.len();
+
+                    // A rest parameter is annotated with an array of the interface generated for its elements
+                    let (parameter_name, new_parameter_type, param_usages) = match &param {
+                        Pattern::RestPattern(rest) => {
+                            let name = rest.pat().map(|pattern| pattern.text()).unwrap_or_default();
+                            let usages = if rules.interfaces {
+                                define_element_type_based_on_usage(&outer_scope, name.as_str())
+                            } else {
+                                None
+                            };
+                            (
+                                name.clone(),
+                                options.naming.interface_name(&name.to_singular()),
+                                usages,
+                            )
+                        }
+                        // The text of a defaulted parameter includes its default, e.g. `a = 32`, so look up its name instead
+                        Pattern::AssignPattern(assign)
+                            if assigned_array_pattern(assign).is_none() =>
+                        {
+                            let name = assign
+                                .syntax()
+                                .child_with_ast::<Name>()
+                                .map(|name| name.text())
+                                .unwrap_or_default();
+                            let usages = if rules.interfaces {
+                                define_type_based_on_usage(&outer_scope, name.as_str())
+                            } else {
+                                None
+                            };
+                            let usages = match assign.value() {
+                                Some(Expr::ObjectExpr(default)) => {
+                                    define_type_based_on_default(name.as_str(), &default, usages)
+                                }
+                                _ if nullable_default(assign).is_some() => usages,
+                                // The default tells us more than the usages would
+                                _ => None,
+                            };
+                            (
+                                name.clone(),
+                                options.naming.interface_name(name.as_str()),
+                                usages,
+                            )
+                        }
+                        _ => {
+                            let name = param.text();
+                            let usages = if rules.interfaces {
+                                define_type_based_on_usage(&outer_scope, name.as_str())
+                            } else {
+                                None
+                            };
+                            (
+                                name.clone(),
+                                options.naming.interface_name(name.as_str()),
+                                usages,
+                            )
+                        }
+                    };
                     debug!("Found param_usages: {param_usages:?} ({parameter_name})");
 
                     match param_usages {
+                        _ if matches!(param, Pattern::SinglePattern(_))
+                            && is_used_as_promise(&outer_scope, &parameter_name) =>
+                        {
+                            let promise = format!("Promise<{fallback_type}>");
+                            if update_pattern(&param, &mut fixer, None, Some(promise), fallback)?
+                                .is_some()
+                            {
+                                summary.parameters += 1;
+                            }
+                        }
                         None => {
-                            update_pattern(&param, &mut fixer, None, None);
+                            if let Some(annotation) =
+                                update_pattern(&param, &mut fixer, None, None, fallback)?
+                            {
+                                summary.parameters += 1;
+                                if is_fallback(&annotation, fallback_type) {
+                                    summary.any_annotations += 1;
+                                    diagnostics.push(Diagnostic::fallback(
+                                        format!("`{parameter_name}`"),
+                                        fallback_type,
+                                        param.range(),
+                                    ));
+                                }
+                            }
+                        }
+                        // Reuse the interface from an earlier run rather than declaring it twice
+                        Some(_) if declared_types.contains(&new_parameter_type) => {
+                            if update_pattern(
+                                &param,
+                                &mut fixer,
+                                None,
+                                Some(new_parameter_type),
+                                fallback,
+                            )?
+                            .is_some()
+                            {
+                                summary.parameters += 1;
+                            }
                         }
                         Some(ref usages) => {
                             let name = get_collision_free_typename(
@@ -70,15 +305,39 @@ pub fn add_types(contents: String) -> String {
 
                             fixer.insert_before(
                                 start_of_file.into(),
-                                create_type_definition(usages, name.as_str()),
+                                create_type_definition(usages, name.as_str(), fallback_type),
                             );
+                            summary.interfaces.push(name.clone());
+                            summary.any_annotations += usages.fallback_fields(fallback_type);
+                            type_definitions.push(TypeDefinition {
+                                name: name.clone(),
+                                ..usages.clone()
+                            });
 
-                            update_pattern(&param, &mut fixer, None, Some(name));
+                            if update_pattern(&param, &mut fixer, None, Some(name), fallback)?
+                                .is_some()
+                            {
+                                summary.parameters += 1;
+                            }
                         }
                     }
+
+                    let title = match summary.interfaces[interfaces..].first() {
+                        Some(interface) => {
+                            format!("Generate interface `{interface}` from the usages of `{parameter_name}`")
+                        }
+                        None => format!("Annotate parameter `{parameter_name}`"),
+                    };
+                    suggestions.extend(Suggestion::from_changes(
+                        &fixer.changes()[first_change..],
+                        title,
+                        param.range(),
+                    ));
                 }
+
+                continue;
             }
-            SyntaxKind::DECLARATOR => {
+            SyntaxKind::DECLARATOR if rules.variables => {
                 let declarator = descendant.to::<Declarator>();
                 if declarator
                     .syntax()
@@ -92,28 +351,127 @@ pub fn add_types(contents: String) -> String {
                 debug!("declarator.pattern: {:?}", declarator.pattern());
 
                 if let Some(ref pattern) = declarator.pattern() {
-                    match declarator.value() {
-                        None => update_pattern(pattern, &mut fixer, None, None),
-                        Some(Expr::Literal(literal)) if literal.is_null() => {
-                            update_pattern(pattern, &mut fixer, None, None)
-                        }
-                        Some(Expr::NameRef(name_ref)) if name_ref.text() == "undefined" => {
-                            update_pattern(pattern, &mut fixer, None, None)
+                    let annotation = match declarator.value() {
+                        _ if !has_ambiguous_initializer(&declarator) => None,
+                        Some(array @ Expr::ArrayExpr(_)) => {
+                            update_pattern(pattern, &mut fixer, Some(array), None, fallback)?
                         }
-                        Some(Expr::ArrayExpr(array)) if array.elements().count() == 0 => {
-                            update_pattern(pattern, &mut fixer, declarator.value(), None)
+                        _ => update_pattern(pattern, &mut fixer, None, None, fallback)?,
+                    };
+
+                    if let Some(annotation) = annotation {
+                        summary.variables += 1;
+                        if is_fallback(&annotation, fallback_type) {
+                            summary.any_annotations += 1;
+                            diagnostics.push(Diagnostic::fallback(
+                                format!("`{}`", pattern.text()),
+                                fallback_type,
+                                pattern.range(),
+                            ));
                         }
-                        _ => (),
                     }
                 }
             }
-            SyntaxKind::CATCH_CLAUSE => {
+            SyntaxKind::CATCH_CLAUSE if rules.catch_clauses => {
                 let catch = descendant.to::<CatchClause>();
                 if let Some(pattern) = catch.error() {
-                    update_pattern(&pattern, &mut fixer, None, None);
+                    if let Some(annotation) =
+                        update_pattern(&pattern, &mut fixer, None, None, catch_fallback)?
+                    {
+                        summary.catch_clauses += 1;
+                        if annotation == catch_fallback.type_name() {
+                            summary.any_annotations += 1;
+                            diagnostics.push(Diagnostic::fallback(
+                                format!("`{}`", pattern.text()),
+                                catch_fallback.type_name(),
+                                pattern.range(),
+                            ));
+                        }
+                    }
+                }
+            }
+            SyntaxKind::FN_DECL
+            | SyntaxKind::FN_EXPR
+            | SyntaxKind::METHOD
+            | SyntaxKind::ARROW_EXPR
+                if rules.return_types =>
+            {
+                let function = match function_signature(&descendant) {
+                    Some(function) => function,
+                    None => continue,
+                };
+
+                let objects: Vec<ObjectExpr> = function
+                    .returns
+                    .iter()
+                    .filter_map(|value| match value.clone().map(without_parentheses) {
+                        Some(Expr::ObjectExpr(object)) => Some(object),
+                        _ => None,
+                    })
+                    .collect();
+                // Separated so a single letter name still ends up as `FResult` rather than `Fresult`
+                let result_name = options.naming.interface_name(&format!(
+                    "{}_result",
+                    function.name.as_deref().unwrap_or("")
+                ));
+                let result = if objects.is_empty() || !rules.interfaces {
+                    None
+                } else {
+                    define_type_based_on_returns(&result_name, &objects)
+                };
+
+                match result {
+                    // Every object literal gets the interface, so give up if that can't be generated
+                    None if !objects.is_empty() => continue,
+                    None => {
+                        if let Some(return_type) = function.return_type(None, fallback_type) {
+                            fixer.insert_after(
+                                function.parameters.range().into(),
+                                format!(": {return_type}"),
+                            );
+                            summary.return_types += 1;
+                        }
+                    }
+                    Some(_) if declared_types.contains(&result_name) => {
+                        if let Some(return_type) =
+                            function.return_type(Some(&result_name), fallback_type)
+                        {
+                            fixer.insert_after(
+                                function.parameters.range().into(),
+                                format!(": {return_type}"),
+                            );
+                            summary.return_types += 1;
+                        }
+                    }
+                    Some(ref definition) => {
+                        let name = get_collision_free_typename(result_name, &mut created_types);
+                        match function.return_type(Some(&name), fallback_type) {
+                            Some(return_type) => {
+                                fixer.insert_before(
+                                    start_of_file.into(),
+                                    create_type_definition(definition, &name, fallback_type),
+                                );
+                                summary.interfaces.push(name.clone());
+                                summary.any_annotations +=
+                                    definition.fallback_fields(fallback_type);
+                                type_definitions.push(TypeDefinition {
+                                    name,
+                                    ..definition.clone()
+                                });
+                                fixer.insert_after(
+                                    function.parameters.range().into(),
+                                    format!(": {return_type}"),
+                                );
+                                summary.return_types += 1;
+                            }
+                            None => {
+                                created_types.remove(&name);
+                            }
+                        }
+                    }
                 }
             }
-            SyntaxKind::CLASS_DECL => {
+            SyntaxKind::CLASS_DECL if rules.react_components => {
                 let class = descendant.to::<ClassDecl>();
 
                 // Here, parent() does not refer to the AST but rather to the type being extended from
@@ -123,41 +481,147 @@ pub fn add_types(contents: String) -> String {
                         let state_fields = define_type_based_on_usage(&ast, "state");
                         debug!("Found props: {props_fields:?}");
 
+                        let props_name = options.naming.interface_name("props");
+                        let state_name = options.naming.interface_name("state");
+
                         match (class.parent_type_args(), &props_fields, &state_fields) {
                             (None, .., Some(state_usages)) => {
                                 let props_definition = props_fields.unwrap_or(TypeDefinition {
-                                    name: "Props".to_string(),
+                                    name: props_name.clone(),
                                     ts_type: TypeDef::NestedType(Vec::new()),
+                                    optional: false,
                                 });
-                                fixer.insert_before(
-                                    start_of_file.into(),
-                                    create_type_definition(&props_definition, "Props"),
-                                );
-                                fixer.insert_before(
-                                    start_of_file.into(),
-                                    create_type_definition(state_usages, "State"),
+                                fixer.insert_after(
+                                    parent.range().into(),
+                                    format!("<{props_name}, {state_name}>"),
                                 );
-                                fixer.insert_after(parent.range().into(), "<Props, State>")
+                                for (name, definition) in
+                                    [(props_name, &props_definition), (state_name, state_usages)]
+                                {
+                                    if !declared_types.contains(&name) {
+                                        fixer.insert_before(
+                                            start_of_file.into(),
+                                            create_type_definition(
+                                                definition,
+                                                &name,
+                                                fallback_type,
+                                            ),
+                                        );
+                                        type_definitions.push(TypeDefinition {
+                                            name: name.clone(),
+                                            ..definition.clone()
+                                        });
+                                        summary.interfaces.push(name);
+                                        summary.any_annotations +=
+                                            definition.fallback_fields(fallback_type);
+                                    }
+                                }
                             }
                             (None, Some(props_usages), None) => {
-                                fixer.insert_before(
-                                    start_of_file.into(),
-                                    create_type_definition(props_usages, "Props"),
+                                fixer
+                                    .insert_after(parent.range().into(), format!("<{props_name}>"));
+                                if !declared_types.contains(&props_name) {
+                                    fixer.insert_before(
+                                        start_of_file.into(),
+                                        create_type_definition(
+                                            props_usages,
+                                            &props_name,
+                                            fallback_type,
+                                        ),
+                                    );
+                                    type_definitions.push(TypeDefinition {
+                                        name: props_name.clone(),
+                                        ..props_usages.clone()
+                                    });
+                                    summary.interfaces.push(props_name);
+                                    summary.any_annotations +=
+                                        props_usages.fallback_fields(fallback_type);
+                                }
+                            }
+                            // Leave the type arguments to the compiler as well
+                            (None, None, None) if fallback == FallbackType::None => continue,
+                            (None, None, None) => {
+                                fixer.insert_after(
+                                    parent.range().into(),
+                                    format!("<{fallback_type}, {fallback_type}>"),
                                 );
-                                fixer.insert_after(parent.range().into(), "<Props>")
+                                summary.any_annotations += 1;
+                                diagnostics.push(Diagnostic::fallback(
+                                    format!(
+                                        "the props and state of `{}`",
+                                        class.name().map(|name| name.text()).unwrap_or_default()
+                                    ),
+                                    fallback_type,
+                                    parent.range(),
+                                ));
                             }
-                            (None, None, None) => fixer.insert_after(parent.range().into(), "<any, any>"),
                             _ => continue,
                         };
+                        summary.components += 1;
                     }
                     _ => continue,
                 }
             }
             _ => continue,
         }
+
+        if let Some((title, span)) = describe_node(&descendant) {
+            suggestions.extend(Suggestion::from_changes(
+                &fixer.changes()[first_change..],
+                title,
+                span,
+            ));
+        }
     }
 
-    fixer.apply()
+    Ok(Inference {
+        editor: fixer,
+        summary,
+        suggestions,
+        type_definitions,
+        diagnostics,
+    })
+}
+
+/// What a suggestion for `node` does and the code it applies to
+fn describe_node(node: &SyntaxNode) -> Option<(String, TextRange)> {
+    match node.kind() {
+        SyntaxKind::DECLARATOR => {
+            let pattern = node.to::<Declarator>().pattern()?;
+            Some((
+                format!("Annotate variable `{}`", pattern.text()),
+                pattern.range(),
+            ))
+        }
+        SyntaxKind::CATCH_CLAUSE => {
+            let pattern = node.to::<CatchClause>().error()?;
+            Some((
+                format!("Annotate `{}` in catch clause", pattern.text()),
+                pattern.range(),
+            ))
+        }
+        SyntaxKind::FN_DECL | SyntaxKind::FN_EXPR | SyntaxKind::METHOD | SyntaxKind::ARROW_EXPR => {
+            let function = function_signature(node)?;
+            let title = match function.name {
+                Some(name) => format!("Annotate the return type of `{name}`"),
+                None => String::from("Annotate the return type"),
+            };
+            Some((
+                title,
+                TextRange::new(node.text_range().start(), function.parameters.range().end()),
+            ))
+        }
+        SyntaxKind::CLASS_DECL => {
+            let class = node.to::<ClassDecl>();
+            let name = class.name().map(|name| name.text()).unwrap_or_default();
+            let end = class.parent()?.range().end();
+            Some((
+                format!("Add props and state types to `{name}`"),
+                TextRange::new(node.text_range().start(), end),
+            ))
+        }
+        _ => None,
+    }
 }
 
 fn update_pattern(
@@ -165,7 +629,9 @@ fn update_pattern(
     fixer: &mut TextEditor,
     expr: Option<Expr>,
     created_type: Option<String>,
-) {
+    fallback: FallbackType,
+) -> Result<Option<String>, TypeRsError> {
+    let fallback_type = fallback.type_name();
     for child in pattern.syntax().children() {
         trace!("child: {child:?}");
     }
@@ -175,41 +641,533 @@ fn update_pattern(
             trace!("single: {single:?}");
             if let Some(span) = single.name().map(|name| name.range()) {
                 if let Some(type_annotation) =
-                    get_type_from_expression(&expr.or(None), &created_type)
+                    get_type_from_expression(&expr.or(None), &created_type, fallback_type)
                 {
-                    debug!("FIXER insert: {span:?}");
-                    fixer.insert_after(span.into(), format!(": {}", type_annotation));
+                    return Ok(annotate(fixer, span, type_annotation, fallback));
                 }
             }
+
+            Ok(None)
+        }
+        Pattern::RestPattern(rest) if rest.ty().is_none() => {
+            let element_type = get_type_from_expression(&None, &created_type, fallback_type)
+                .unwrap_or_else(|| String::from(fallback_type));
+            Ok(annotate(
+                fixer,
+                rest.range(),
+                format!("{element_type}[]"),
+                fallback,
+            ))
         }
-        Pattern::RestPattern(_) => todo!(),
         Pattern::AssignPattern(assign) if assign.ty().is_none() => {
+            if let Some(array) = assigned_array_pattern(assign) {
+                return Ok(annotate_array_pattern(
+                    fixer,
+                    &array,
+                    assign.value(),
+                    fallback,
+                ));
+            }
+
+            // `null` and `undefined` defaults have to stay assignable to the generated interface
+            let created_type = match (created_type, nullable_default(assign)) {
+                (Some(created_type), Some(default)) => Some(format!("{created_type} | {default}")),
+                (created_type, _) => created_type,
+            };
+
             // FIXME: AssignPattern.key() returns None so we work around it by querying the children instead. Should be Pattern::SinglePattern
-            if let Some(type_annotation) =
-                get_type_from_expression(&expr.or_else(|| assign.value()), &created_type)
-            {
+            if let Some(type_annotation) = get_type_from_expression(
+                &expr.or_else(|| assign.value()),
+                &created_type,
+                fallback_type,
+            ) {
                 if let Some(name) = assign.syntax().child_with_ast::<Name>() {
-                    debug!("FIXER insert: {:?}", name.range());
-                    fixer.insert_after(name.range().into(), format!(": {}", type_annotation));
+                    return Ok(annotate(fixer, name.range(), type_annotation, fallback));
                 }
             }
+
+            Ok(None)
         }
         Pattern::ObjectPattern(obj) if obj.ty().is_none() => {
-            if let Some(type_annotation) = get_type_from_expression(&expr.or(None), &created_type) {
-                debug!("FIXER insert: {:?}", obj.range());
-                fixer.insert_after(obj.range().into(), format!(": {}", type_annotation));
+            if let Some(type_annotation) =
+                get_type_from_expression(&expr.or(None), &created_type, fallback_type)
+            {
+                return Ok(annotate(fixer, obj.range(), type_annotation, fallback));
             }
+
+            Ok(None)
         }
-        // Pattern::ArrayPattern(array) => {
-        //     debug!("array pattern: {:?}", array.text());
-        // }
+        Pattern::ArrayPattern(array) if array.ty().is_none() => Ok(annotate_array_pattern(
+            fixer,
+            array.syntax(),
+            expr,
+            fallback,
+        )),
         // Pattern::ExprPattern(_) => todo!(),
-        _ => (),
+        _ => Ok(None),
     }
 }
 
+/// Inserts `type_annotation` after `span`, unless it's the fallback and that should be left to the compiler
+fn annotate(
+    fixer: &mut TextEditor,
+    span: TextRange,
+    type_annotation: String,
+    fallback: FallbackType,
+) -> Option<String> {
+    if fallback == FallbackType::None && is_fallback(&type_annotation, fallback.type_name()) {
+        debug!("Leaving {span:?} unannotated");
+        return None;
+    }
+
+    debug!("FIXER insert: {span:?}");
+    fixer.insert_after(span.into(), format!(": {}", type_annotation));
+    Some(type_annotation)
+}
+
+/// The parts of a function, method or arrow function without a return type that determine its return type
+struct FunctionSignature {
+    name: Option<String>,
+    parameters: ParameterList,
+    is_async: bool,
+    is_generator: bool,
+    /// The values of its `return` statements, `None` for a bare `return`
+    returns: Vec<Option<Expr>>,
+    /// The values of its `yield` expressions, `None` for a bare `yield`
+    yields: Vec<Option<Expr>>,
+}
+
+impl FunctionSignature {
+    /// The return type with object literals typed as `result_type`, wrapped in a `Promise` or `Generator` if need be
+    fn return_type(&self, result_type: Option<&str>, fallback_type: &str) -> Option<String> {
+        let returns = union_type(&self.returns, result_type, fallback_type)?;
+        if !self.is_generator {
+            return Some(if self.is_async {
+                format!("Promise<{returns}>")
+            } else {
+                returns
+            });
+        }
+
+        let yields = if self.yields.is_empty() {
+            String::from("never")
+        } else {
+            union_type(&self.yields, None, fallback_type)?
+        };
+        let generator = if self.is_async {
+            "AsyncGenerator"
+        } else {
+            "Generator"
+        };
+        Some(format!("{generator}<{yields}, {returns}, any>"))
+    }
+}
+
+fn function_signature(node: &SyntaxNode) -> Option<FunctionSignature> {
+    let (name, parameters, body, has_return_type, is_async, is_generator) = match node.kind() {
+        SyntaxKind::FN_DECL => {
+            let function = node.to::<FnDecl>();
+            (
+                function.name().map(|name| name.text()),
+                function.parameters(),
+                function.body().map(ExprOrBlock::Block),
+                function.return_type().is_some(),
+                is_async(node),
+                function.star_token().is_some(),
+            )
+        }
+        SyntaxKind::FN_EXPR => {
+            let function = node.to::<FnExpr>();
+            (
+                function
+                    .name()
+                    .map(|name| name.text())
+                    .or_else(|| assigned_name(node)),
+                function.parameters(),
+                function.body().map(ExprOrBlock::Block),
+                function.return_type().is_some(),
+                is_async(node),
+                function.star_token().is_some(),
+            )
+        }
+        SyntaxKind::METHOD => {
+            let method = node.to::<Method>();
+            (
+                method
+                    .name()
+                    .filter(|name| !matches!(name, PropName::Computed(_)))
+                    .map(|name| name.text()),
+                method.parameters(),
+                method.body().map(ExprOrBlock::Block),
+                method.return_type().is_some(),
+                is_async(node),
+                method.star_token().is_some(),
+            )
+        }
+        SyntaxKind::ARROW_EXPR => {
+            let arrow = node.to::<ArrowExpr>();
+            // A single parameter without parentheses leaves nowhere to put the return type
+            let parameters = match arrow.params() {
+                Some(ArrowExprParams::ParameterList(parameters)) => Some(parameters),
+                _ => None,
+            };
+            (
+                assigned_name(node),
+                parameters,
+                arrow.body(),
+                arrow.return_type().is_some(),
+                is_async(node),
+                false,
+            )
+        }
+        _ => return None,
+    };
+
+    if has_return_type {
+        return None;
+    }
+
+    let (returns, yields) = match body? {
+        ExprOrBlock::Expr(expr) => (vec![Some(expr)], vec![]),
+        ExprOrBlock::Block(block) => {
+            let own_descendants: Vec<SyntaxNode> = block
+                .syntax()
+                .descendants()
+                .filter(|descendant| {
+                    descendant.ancestors().find(is_function).as_ref() == Some(node)
+                })
+                .collect();
+            let mut returns: Vec<Option<Expr>> = own_descendants
+                .iter()
+                .filter(|descendant| descendant.is::<ReturnStmt>())
+                .map(|ret| ret.to::<ReturnStmt>().value())
+                .collect();
+            // Falling off the end returns `undefined` as well
+            let falls_through = !block.stmts().last().is_some_and(|stmt| terminates(&stmt));
+            if !returns.is_empty() && falls_through {
+                returns.push(None);
+            }
+            let yields: Vec<YieldExpr> = own_descendants
+                .iter()
+                .filter(|descendant| descendant.is::<YieldExpr>())
+                .map(|descendant| descendant.to::<YieldExpr>())
+                .collect();
+            // We can't tell what `yield*` delegates to
+            if yields
+                .iter()
+                .any(|yield_expr| yield_expr.star_token().is_some())
+            {
+                return None;
+            }
+
+            (returns, yields.iter().map(YieldExpr::value).collect())
+        }
+    };
+
+    Some(FunctionSignature {
+        name,
+        parameters: parameters?,
+        is_async,
+        is_generator,
+        returns,
+        yields,
+    })
+}
+
+/// Whether control never continues after `stmt` because every path through it returns or throws.
+/// Errs on the side of falling through, e.g. for loops or a `switch` containing a `break`.
+fn terminates(stmt: &Stmt) -> bool {
+    let ends_in_termination = |stmts: &[Stmt]| stmts.last().is_some_and(terminates);
+    let block_terminates = |block: Option<BlockStmt>| {
+        block.is_some_and(|block| ends_in_termination(&block.stmts().collect::<Vec<_>>()))
+    };
+    match stmt {
+        Stmt::ReturnStmt(_) | Stmt::ThrowStmt(_) => true,
+        Stmt::BlockStmt(block) => block_terminates(Some(block.clone())),
+        Stmt::IfStmt(if_stmt) => {
+            if_stmt.cons().is_some_and(|cons| terminates(&cons))
+                && if_stmt.alt().is_some_and(|alt| terminates(&alt))
+        }
+        Stmt::SwitchStmt(switch) => {
+            let mut has_default = false;
+            let clauses: Vec<Vec<Stmt>> = switch
+                .cases()
+                .map(|case| match case {
+                    SwitchCase::CaseClause(clause) => clause.cons().collect(),
+                    SwitchCase::DefaultClause(clause) => {
+                        has_default = true;
+                        clause.cons().collect()
+                    }
+                })
+                .collect();
+            // Empty clauses fall into the next one, which has to terminate in their place
+            has_default
+                && !switch
+                    .syntax()
+                    .descendants()
+                    .any(|node| node.is::<BreakStmt>())
+                && clauses
+                    .last()
+                    .is_some_and(|stmts| ends_in_termination(stmts))
+                && clauses
+                    .iter()
+                    .all(|stmts| stmts.is_empty() || ends_in_termination(stmts))
+        }
+        Stmt::TryStmt(try_stmt) => {
+            let finalizer = try_stmt.finalizer().and_then(|finalizer| finalizer.cons());
+            let handler_terminates = match try_stmt.handler() {
+                Some(handler) => block_terminates(handler.cons()),
+                None => true,
+            };
+            block_terminates(finalizer) || (block_terminates(try_stmt.test()) && handler_terminates)
+        }
+        _ => false,
+    }
+}
+
+/// rslint only gives methods an `async` keyword token, functions have an identifier instead
+fn is_async(node: &SyntaxNode) -> bool {
+    node.children_with_tokens()
+        .filter_map(|child| child.into_token())
+        .any(|token| token.text() == "async")
+}
+
+fn is_function(node: &SyntaxNode) -> bool {
+    node.is::<FnDecl>()
+        || node.is::<FnExpr>()
+        || node.is::<ArrowExpr>()
+        || node.is::<Method>()
+        || node.is::<Constructor>()
+        || node.is::<Getter>()
+        || node.is::<Setter>()
+}
+
+/// The name of the variable or property an anonymous function is assigned to
+fn assigned_name(node: &SyntaxNode) -> Option<String> {
+    let parent = node.parent()?;
+    if parent.is::<Declarator>() {
+        match parent.to::<Declarator>().pattern()? {
+            Pattern::SinglePattern(single) => single.name().map(|name| name.text()),
+            _ => None,
+        }
+    } else if parent.is::<LiteralProp>() {
+        match parent.to::<LiteralProp>().key()? {
+            PropName::Computed(_) => None,
+            key => Some(key.text()),
+        }
+    } else {
+        None
+    }
+}
+
+fn without_parentheses(expr: Expr) -> Expr {
+    match expr {
+        Expr::GroupingExpr(grouping) => grouping
+            .inner()
+            .map_or(Expr::GroupingExpr(grouping), without_parentheses),
+        expr => expr,
+    }
+}
+
+/// A union of the types of `values`, `void` if there's nothing to return. Object literals are `result_type`.
+/// `None` if any of them could be anything.
+fn union_type(
+    values: &[Option<Expr>],
+    result_type: Option<&str>,
+    fallback_type: &str,
+) -> Option<String> {
+    let mut types: Vec<String> = vec![];
+    for value in values {
+        let value_type = match value.clone().map(without_parentheses) {
+            None => String::from("undefined"),
+            Some(Expr::ObjectExpr(_)) => result_type?.to_string(),
+            Some(Expr::Literal(literal)) if literal.is_null() => String::from("null"),
+            Some(Expr::NameRef(name_ref)) if name_ref.text() == "undefined" => {
+                String::from("undefined")
+            }
+            Some(Expr::Template(_)) => String::from("string"),
+            Some(expr @ (Expr::Literal(_) | Expr::ArrayExpr(_))) => {
+                get_type_from_expression(&Some(expr), &None, fallback_type)
+                    .filter(|value_type| !is_fallback(value_type, fallback_type))?
+            }
+            Some(Expr::CallExpr(call)) if call.callee()?.text() == "BigInt" => {
+                String::from("BigInt")
+            }
+            Some(_) => return None,
+        };
+        if !types.contains(&value_type) {
+            types.push(value_type);
+        }
+    }
+
+    match types.as_slice() {
+        [] => Some(String::from("void")),
+        [only] if only == "undefined" => Some(String::from("void")),
+        _ => Some(types.join(" | ")),
+    }
+}
+
+/// `null` or `undefined` if that's what `assign` defaults to
+fn nullable_default(assign: &AssignPattern) -> Option<&'static str> {
+    match assign.value()? {
+        Expr::Literal(literal) if literal.kind() == LiteralKind::Null => Some("null"),
+        Expr::NameRef(name_ref) if name_ref.text() == "undefined" => Some("undefined"),
+        _ => None,
+    }
+}
+
+/// `pattern` is an `ArrayPattern`, or an `AssignPattern` standing in for one, see [`assigned_array_pattern`]
+fn annotate_array_pattern(
+    fixer: &mut TextEditor,
+    pattern: &SyntaxNode,
+    initializer: Option<Expr>,
+    fallback: FallbackType,
+) -> Option<String> {
+    let (tuple, inferred) = tuple_type(pattern, initializer, fallback.type_name());
+    if !inferred && fallback == FallbackType::None {
+        debug!("Leaving {:?} unannotated", pattern.text_range());
+        return None;
+    }
+
+    let closing_bracket = pattern
+        .children_with_tokens()
+        .take_while(|child| child.kind() != SyntaxKind::EQ)
+        .filter(|child| child.kind() == SyntaxKind::R_BRACK)
+        .last()?;
+    annotate(fixer, closing_bracket.text_range(), tuple, fallback)
+}
+
+/// The array pattern of `[a, b] = [1, 2]`. For parameters rslint doesn't give it a node of its own,
+/// its elements end up in the `AssignPattern` instead, followed by the default value.
+fn assigned_array_pattern(assign: &AssignPattern) -> Option<SyntaxNode> {
+    match assign.syntax().first_child_or_token()? {
+        child if child.kind() == SyntaxKind::L_BRACK => Some(assign.syntax().clone()),
+        child => child.into_node().filter(|node| node.is::<ArrayPattern>()),
+    }
+}
+
+/// The tuple type of an array pattern such as `[a = 1, [b, c], , ...d]`, and whether any element type was inferred.
+/// Element types come from the defaults in the pattern or the matching elements of `initializer`.
+/// Elements the initializer doesn't provide are optional.
+fn tuple_type(
+    pattern: &SyntaxNode,
+    initializer: Option<Expr>,
+    fallback_type: &str,
+) -> (String, bool) {
+    let values: Option<Vec<Expr>> = match initializer {
+        Some(Expr::ArrayExpr(array_expr)) => array_expr
+            .elements()
+            .map(|element| match element {
+                ExprOrSpread::Expr(expr) => Some(expr),
+                ExprOrSpread::Spread(_) => None,
+            })
+            .collect(),
+        _ => None,
+    };
+
+    let mut elements: Vec<(String, bool)> = vec![];
+    let mut current = None;
+    // Holes don't have a node of their own, only the commas around them show where they are
+    for child in pattern.children_with_tokens() {
+        match child.kind() {
+            SyntaxKind::EQ => break,
+            SyntaxKind::COMMA => elements.push(
+                current
+                    .take()
+                    .unwrap_or_else(|| (String::from(fallback_type), false)),
+            ),
+            _ => {
+                if let Some(element) = child.into_node().and_then(Pattern::cast) {
+                    let value = values
+                        .as_ref()
+                        .and_then(|values| values.get(elements.len()).cloned());
+                    current = Some(element_type(&element, value, fallback_type));
+                }
+            }
+        }
+    }
+    elements.extend(current);
+
+    if let Some(values) = &values {
+        for (element_type, _) in elements.iter_mut().skip(values.len()) {
+            if !element_type.starts_with("...") {
+                element_type.push('?');
+            }
+        }
+    }
+
+    let inferred = elements.iter().any(|(_, inferred)| *inferred);
+    let element_types: Vec<String> = elements
+        .into_iter()
+        .map(|(element_type, _)| element_type)
+        .collect();
+    (format!("[{}]", element_types.join(", ")), inferred)
+}
+
+fn element_type(pattern: &Pattern, value: Option<Expr>, fallback_type: &str) -> (String, bool) {
+    match pattern {
+        Pattern::ArrayPattern(array) => tuple_type(array.syntax(), value, fallback_type),
+        Pattern::AssignPattern(assign) => match assigned_array_pattern(assign) {
+            Some(array) => tuple_type(&array, assign.value(), fallback_type),
+            None => expression_type(assign.value(), fallback_type),
+        },
+        Pattern::RestPattern(_) => (format!("...{fallback_type}[]"), false),
+        _ => expression_type(value, fallback_type),
+    }
+}
+
+fn expression_type(expr: Option<Expr>, fallback_type: &str) -> (String, bool) {
+    match get_type_from_expression(&expr, &None, fallback_type) {
+        Some(expression_type) if !is_fallback(&expression_type, fallback_type) => {
+            (expression_type, true)
+        }
+        _ => (String::from(fallback_type), false),
+    }
+}
+
+/// Whether nothing better than the fallback type could be inferred, including arrays of unknown elements
+fn is_fallback(type_annotation: &str, fallback_type: &str) -> bool {
+    type_annotation == fallback_type || type_annotation == format!("{fallback_type}[]")
+}
+
+/// Patterns the author already annotated are left alone
+fn has_type_annotation(pattern: &Pattern) -> bool {
+    type_annotation(pattern).is_some()
+}
+
+pub(crate) fn type_annotation(pattern: &Pattern) -> Option<TsType> {
+    match pattern {
+        Pattern::SinglePattern(single) => single.ty(),
+        Pattern::RestPattern(rest) => rest.ty(),
+        Pattern::AssignPattern(assign) => assign.ty(),
+        Pattern::ObjectPattern(obj) => obj.ty(),
+        Pattern::ArrayPattern(array) => array.ty(),
+        Pattern::ExprPattern(_) => None,
+    }
+}
+
+/// Variables initialized like this would end up as `any` or `never[]` unless they're annotated
+pub(crate) fn has_ambiguous_initializer(declarator: &Declarator) -> bool {
+    match declarator.value() {
+        None => true,
+        Some(Expr::Literal(literal)) => literal.is_null(),
+        Some(Expr::NameRef(name_ref)) => name_ref.text() == "undefined",
+        Some(Expr::ArrayExpr(array)) => array.elements().count() == 0,
+        Some(_) => false,
+    }
+}
+
+/// Names of the interfaces and type aliases declared in the file, e.g. by an earlier run of `add_types`
+pub(crate) fn find_declared_types(ast: &SyntaxNode) -> HashSet<String> {
+    ast.descendants()
+        .filter(|node| node.is::<TsInterfaceDecl>() || node.is::<TsTypeAliasDecl>())
+        .filter_map(|node| node.child_with_ast::<Name>())
+        .map(|name| name.text())
+        .collect()
+}
+
 fn is_react_component_class(expr: &Expr) -> bool {
-    let class_names = vec!["Component", "PureComponent"];
+    let class_names = ["Component", "PureComponent"];
 
     match expr {
         Expr::NameRef(name_ref) => class_names.contains(&name_ref.text().as_str()),
@@ -0,0 +1,55 @@
+use std::any::Any;
+use std::num::NonZeroUsize;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Mutex;
+use std::thread;
+
+/// Number of workers to use when the user doesn't specify it: one per CPU
+pub fn default_jobs() -> NonZeroUsize {
+    thread::available_parallelism().unwrap_or(NonZeroUsize::MIN)
+}
+
+/// Runs `handler` for every item on at most `jobs` threads and waits until all items are processed.
+/// A panic only takes down the item that caused it, which then gets the panic message as its result.
+/// Results are returned in the same order as the items.
+pub fn process_all<T, R, F>(items: Vec<T>, jobs: NonZeroUsize, handler: F) -> Vec<Result<R, String>>
+where
+    T: Send,
+    R: Send,
+    F: Fn(T) -> R + Sync,
+{
+    let item_count = items.len();
+    let queue = Mutex::new(items.into_iter().enumerate());
+    let results = Mutex::new(Vec::with_capacity(item_count));
+
+    thread::scope(|scope| {
+        for _ in 0..jobs.get().min(item_count) {
+            scope.spawn(|| loop {
+                // Bind the item first so the queue is unlocked while we process it
+                let next = queue.lock().unwrap().next();
+                let (index, item) = match next {
+                    Some(next) => next,
+                    None => break,
+                };
+
+                let result =
+                    panic::catch_unwind(AssertUnwindSafe(|| handler(item))).map_err(panic_message);
+                results.lock().unwrap().push((index, result));
+            });
+        }
+    });
+
+    let mut results = results.into_inner().unwrap();
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+pub(crate) fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        String::from("unknown panic")
+    }
+}
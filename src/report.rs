@@ -0,0 +1,102 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::error::TypeRsError;
+use crate::parser::ConversionSummary;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileStatus {
+    Converted,
+    /// The file was renamed but we didn't find anything to annotate
+    Unchanged,
+    SkippedFlow,
+    Panicked,
+    ReadError,
+    /// The conversion returned an error, or writing the result failed
+    Failed,
+    Excluded,
+}
+
+impl FileStatus {
+    pub fn is_failure(&self) -> bool {
+        matches!(
+            self,
+            FileStatus::Panicked | FileStatus::ReadError | FileStatus::Failed
+        )
+    }
+}
+
+/// What happened to a single file we came across
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FileReport {
+    pub source: PathBuf,
+    pub target: Option<PathBuf>,
+    pub status: FileStatus,
+    pub parameter_annotations: usize,
+    pub variable_annotations: usize,
+    pub catch_annotations: usize,
+    pub interfaces: Vec<String>,
+    pub any_annotations: usize,
+    /// `None` if the file was never converted, for example because it panicked
+    pub elapsed_ms: Option<f64>,
+    pub error: Option<String>,
+}
+
+impl FileReport {
+    /// A report for a file that didn't get far enough to be converted
+    pub fn new(source: &Path, status: FileStatus) -> FileReport {
+        FileReport {
+            source: source.to_path_buf(),
+            target: None,
+            status,
+            parameter_annotations: 0,
+            variable_annotations: 0,
+            catch_annotations: 0,
+            interfaces: vec![],
+            any_annotations: 0,
+            elapsed_ms: None,
+            error: None,
+        }
+    }
+
+    pub fn failed(source: &Path, error: &TypeRsError) -> FileReport {
+        let status = match error {
+            TypeRsError::Read { .. } => FileStatus::ReadError,
+            _ => FileStatus::Failed,
+        };
+
+        FileReport {
+            error: Some(error.to_string()),
+            ..FileReport::new(source, status)
+        }
+    }
+
+    pub fn converted(
+        source: &Path,
+        target: &Path,
+        status: FileStatus,
+        summary: &ConversionSummary,
+        elapsed: Duration,
+    ) -> FileReport {
+        FileReport {
+            target: Some(target.to_path_buf()),
+            parameter_annotations: summary.parameters,
+            variable_annotations: summary.variables,
+            catch_annotations: summary.catch_clauses,
+            interfaces: summary.interfaces.clone(),
+            any_annotations: summary.any_annotations,
+            elapsed_ms: Some(elapsed.as_secs_f64() * 1000.0),
+            ..FileReport::new(source, status)
+        }
+    }
+}
+
+pub fn write_report(path: &Path, reports: &[FileReport]) -> io::Result<()> {
+    let json = serde_json::to_string_pretty(reports)?;
+    fs::write(path, json)
+}
@@ -0,0 +1,255 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write as _;
+use std::fs;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use log::warn;
+use rslint_parser::{
+    ast::{Declarator, ForStmtInit, ParameterList},
+    parse_with_syntax, AstNode, Syntax, SyntaxKind, SyntaxNodeExt,
+};
+use serde::Serialize;
+
+use crate::config::ConfigResolver;
+use crate::parser::{find_declared_types, has_ambiguous_initializer, type_annotation};
+use crate::pool::process_all;
+use crate::traversal::{find_source_files, FileFilter};
+
+/// How the parameters or variables of some code are annotated
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct AnnotationStats {
+    /// Annotated with one of the types `add_types` falls back to: `any`, `unknown` or an array of them
+    pub any: usize,
+    /// Annotated with an interface or type alias declared in the same file, like the ones `add_types` generates
+    pub interface: usize,
+    pub concrete: usize,
+    /// Not annotated even though `add_types` would annotate it
+    pub missing: usize,
+}
+
+impl AnnotationStats {
+    pub fn total(&self) -> usize {
+        self.any + self.interface + self.concrete + self.missing
+    }
+
+    fn typed(&self) -> usize {
+        self.interface + self.concrete
+    }
+
+    fn add(&mut self, other: &AnnotationStats) {
+        self.any += other.any;
+        self.interface += other.interface;
+        self.concrete += other.concrete;
+        self.missing += other.missing;
+    }
+
+    fn count(&mut self, annotation: Option<String>, declared_types: &HashSet<String>) {
+        match annotation.as_deref().map(str::trim) {
+            None => self.missing += 1,
+            Some("any" | "unknown" | "any[]" | "unknown[]") => self.any += 1,
+            Some(name) if declared_types.contains(name) => self.interface += 1,
+            Some(_) => self.concrete += 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileStats {
+    pub path: PathBuf,
+    pub typescript: bool,
+    pub parameters: AnnotationStats,
+    pub variables: AnnotationStats,
+}
+
+/// The numbers of a directory and everything below it
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct DirectoryStats {
+    pub path: PathBuf,
+    pub javascript_files: usize,
+    pub typescript_files: usize,
+    pub parameters: AnnotationStats,
+    pub variables: AnnotationStats,
+}
+
+impl DirectoryStats {
+    fn annotations(&self) -> AnnotationStats {
+        let mut annotations = self.parameters;
+        annotations.add(&self.variables);
+        annotations
+    }
+
+    /// Share of the parameters and variables which have a type other than `any`
+    pub fn coverage(&self) -> f64 {
+        let annotations = self.annotations();
+        ratio(annotations.typed(), annotations.total())
+    }
+
+    /// Share of the annotations which are `any`
+    pub fn any_density(&self) -> f64 {
+        let annotations = self.annotations();
+        ratio(annotations.any, annotations.any + annotations.typed())
+    }
+}
+
+fn ratio(count: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        count as f64 / total as f64
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct Stats {
+    /// Sorted by path, starting with the root which holds the totals
+    pub directories: Vec<DirectoryStats>,
+    pub files: Vec<FileStats>,
+}
+
+impl Stats {
+    pub fn to_table(&self) -> String {
+        let width = self
+            .directories
+            .iter()
+            .map(|directory| directory.path.as_os_str().len())
+            .chain([9])
+            .max()
+            .unwrap_or_default();
+
+        let mut table = format!(
+            "{:<width$}  {:>5}  {:>5}  {:>6}  {:>5}  {:>7}  {:>8}  {:>11}\n",
+            "Directory", "JS", "TS", "Typed", "Any", "Missing", "Coverage", "Any density"
+        );
+        for directory in &self.directories {
+            let annotations = directory.annotations();
+            let _ = writeln!(
+                table,
+                "{:<width$}  {:>5}  {:>5}  {:>6}  {:>5}  {:>7}  {:>7.1}%  {:>10.1}%",
+                directory.path.display(),
+                directory.javascript_files,
+                directory.typescript_files,
+                annotations.typed(),
+                annotations.any,
+                annotations.missing,
+                directory.coverage() * 100.0,
+                directory.any_density() * 100.0,
+            );
+        }
+
+        table
+    }
+}
+
+/// Counts the annotations of every source file below `root` on `jobs` threads and rolls them up per directory.
+/// Paths in the result are relative to `root`.
+pub fn collect_stats(
+    root: &Path,
+    filter: &FileFilter,
+    configs: &Arc<ConfigResolver>,
+    jobs: NonZeroUsize,
+) -> Stats {
+    let filter = filter.clone().with_typescript(true);
+    let files = find_source_files(root, &filter, configs).files;
+    let paths: Vec<PathBuf> = files.iter().map(|file| file.path.clone()).collect();
+
+    let results = process_all(files, jobs, |file| match fs::read_to_string(&file.path) {
+        Ok(contents) => {
+            let path = file
+                .path
+                .strip_prefix(root)
+                .unwrap_or(&file.path)
+                .to_path_buf();
+            Some(file_stats(path, file.is_typescript(), &contents))
+        }
+        Err(err) => {
+            warn!("Skipped {:?}: {err}", file.path);
+            None
+        }
+    });
+    let mut files: Vec<FileStats> = results
+        .into_iter()
+        .zip(&paths)
+        .filter_map(|(result, path)| {
+            result.unwrap_or_else(|panic| {
+                warn!("Skipped {path:?}: counting its annotations panicked: {panic}");
+                None
+            })
+        })
+        .collect();
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let mut directories: BTreeMap<PathBuf, DirectoryStats> = BTreeMap::new();
+    for file in &files {
+        for directory in file.path.ancestors().skip(1) {
+            let directory = if directory.as_os_str().is_empty() {
+                Path::new(".")
+            } else {
+                directory
+            };
+
+            let stats = directories
+                .entry(directory.to_path_buf())
+                .or_insert_with(|| DirectoryStats {
+                    path: directory.to_path_buf(),
+                    ..DirectoryStats::default()
+                });
+            if file.typescript {
+                stats.typescript_files += 1;
+            } else {
+                stats.javascript_files += 1;
+            }
+            stats.parameters.add(&file.parameters);
+            stats.variables.add(&file.variables);
+        }
+    }
+
+    Stats {
+        directories: directories.into_values().collect(),
+        files,
+    }
+}
+
+/// Walks the parameters and variables `add_types` looks at and sorts them by their annotation
+pub fn file_stats(path: PathBuf, typescript: bool, contents: &str) -> FileStats {
+    let parse = parse_with_syntax(contents, 0, Syntax::default().typescript());
+    let ast = parse.syntax();
+    let declared_types = find_declared_types(&ast);
+
+    let mut parameters = AnnotationStats::default();
+    let mut variables = AnnotationStats::default();
+    for descendant in ast.descendants() {
+        match descendant.kind() {
+            SyntaxKind::PARAMETER_LIST => {
+                for param in descendant.to::<ParameterList>().parameters() {
+                    parameters.count(type_annotation(&param).map(|ty| ty.text()), &declared_types);
+                }
+            }
+            SyntaxKind::DECLARATOR => {
+                let declarator = descendant.to::<Declarator>();
+                if declarator
+                    .syntax()
+                    .ancestors()
+                    .any(|ancestor| ancestor.is::<ForStmtInit>())
+                {
+                    continue;
+                }
+
+                match declarator.pattern().as_ref().and_then(type_annotation) {
+                    Some(ty) => variables.count(Some(ty.text()), &declared_types),
+                    None if has_ambiguous_initializer(&declarator) => variables.missing += 1,
+                    None => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    FileStats {
+        path,
+        typescript,
+        parameters,
+        variables,
+    }
+}
@@ -1,16 +1,24 @@
 use log::debug;
 
-struct Change(usize, String);
+/// Text inserted at a byte offset of the original source
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    pub offset: usize,
+    pub text: String,
+}
 
 #[derive(Debug)]
 pub struct Range {
     start: usize,
-    end: usize
+    end: usize,
 }
 
 impl From<rslint_parser::TextRange> for Range {
     fn from(text_range: rslint_parser::TextRange) -> Self {
-        Range { start: text_range.start().into(), end: text_range.end().into() }
+        Range {
+            start: text_range.start().into(),
+            end: text_range.end().into(),
+        }
     }
 }
 
@@ -26,6 +34,13 @@ pub struct TextEditor {
     source: String,
 }
 
+impl TextEditor {
+    /// The changes in the order they were made
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+}
+
 impl TextEdit for TextEditor {
     fn load(text: impl ToString) -> TextEditor {
         TextEditor {
@@ -36,35 +51,40 @@ impl TextEdit for TextEditor {
 
     fn insert_after(&mut self, range: Range, text: impl ToString) {
         debug!("FIXER insert_after: {:?}", range);
-        self.changes
-            .push(Change(range.end, text.to_string()));
+        self.changes.push(Change {
+            offset: range.end,
+            text: text.to_string(),
+        });
     }
 
     fn insert_before(&mut self, range: Range, text: impl ToString) {
         debug!("FIXER insert_before: {:?}", range);
-        self.changes
-            .push(Change(range.start, text.to_string()));
+        self.changes.push(Change {
+            offset: range.start,
+            text: text.to_string(),
+        });
     }
 
     fn apply(&mut self) -> String {
         let new_source_length = {
             let total_insertion_length: usize =
-                self.changes.iter().map(|change| change.1.len()).sum();
+                self.changes.iter().map(|change| change.text.len()).sum();
 
             self.source.len() + total_insertion_length
         };
 
-        self.changes.sort_by(|a, b| a.0.cmp(&b.0));
+        // The sort is stable so insertions at the same offset keep the order they were made in
+        self.changes.sort_by_key(|change| change.offset);
         let mut buf = String::with_capacity(new_source_length);
         let mut pointer = 0usize;
 
         for change in &self.changes {
-            let current_pointer = change.0;
+            let current_pointer = change.offset;
             if current_pointer > pointer {
                 buf.push_str(&self.source[pointer..current_pointer]);
             }
 
-            buf.push_str(change.1.as_str());
+            buf.push_str(change.text.as_str());
             pointer = current_pointer
         }
         buf.push_str(&self.source[pointer..self.source.len()]);
@@ -0,0 +1,298 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use ignore::WalkBuilder;
+use log::{debug, error, warn};
+
+use crate::cache::CACHE_DIRECTORY;
+use crate::config::{ConfigResolver, ResolvedConfig};
+use crate::journal::JOURNAL_DIRECTORY;
+
+/// Directories and files which are never worth converting: dependencies, build output and minified bundles
+pub const DEFAULT_EXCLUDES: &[&str] = &[
+    "**/node_modules",
+    "**/bower_components",
+    "**/vendor",
+    "**/dist",
+    "**/build",
+    "**/coverage",
+    "**/*.min.js",
+    "**/*.bundle.js",
+];
+
+/// Directories of version control and of type-rs itself. Unlike excluded paths they're skipped silently
+/// and never copied along, while other hidden directories such as `.storybook` are converted like any other.
+/// The cache and journal are bookkeeping for the tree being converted rather than part of it.
+const SKIPPED_DIRECTORIES: &[&str] = &[".git", ".hg", ".svn", CACHE_DIRECTORY, JOURNAL_DIRECTORY];
+
+#[derive(Debug)]
+pub struct SourceFile {
+    /// A `.js`, `.jsx` or, when re-running over a partial migration, `.ts` or `.tsx` file
+    pub path: PathBuf,
+    pub file_name: String,
+    pub extension: &'static str,
+    /// Configuration of the directory the file lives in
+    pub config: Arc<ResolvedConfig>,
+}
+
+impl SourceFile {
+    /// Whether the file was converted before and keeps its name
+    pub fn is_typescript(&self) -> bool {
+        matches!(
+            self.path.extension().and_then(|ext| ext.to_str()),
+            Some("ts") | Some("tsx")
+        )
+    }
+}
+
+/// Everything we came across while walking the tree
+#[derive(Debug, Default)]
+pub struct Traversal {
+    pub files: Vec<SourceFile>,
+    /// Source files and whole directories that were skipped because of the include and exclude patterns
+    pub excluded: Vec<PathBuf>,
+}
+
+/// Decides which paths take part in a conversion based on glob patterns relative to the root directory
+#[derive(Clone)]
+pub struct FileFilter {
+    includes: Option<GlobSet>,
+    excludes: GlobSet,
+    typescript: bool,
+}
+
+impl FileFilter {
+    /// An empty `includes` list accepts every file. `excludes` are applied on top of [`DEFAULT_EXCLUDES`].
+    pub fn new(includes: &[String], excludes: &[String]) -> Result<FileFilter, globset::Error> {
+        let includes = if includes.is_empty() {
+            None
+        } else {
+            Some(build_glob_set(includes.iter().map(String::as_str))?)
+        };
+
+        let excludes = build_glob_set(
+            DEFAULT_EXCLUDES
+                .iter()
+                .copied()
+                .chain(excludes.iter().map(String::as_str)),
+        )?;
+
+        Ok(FileFilter {
+            includes,
+            excludes,
+            typescript: false,
+        })
+    }
+
+    /// Also accept `.ts` and `.tsx` files so a partial migration can be picked up where it was left
+    pub fn with_typescript(self, typescript: bool) -> FileFilter {
+        FileFilter { typescript, ..self }
+    }
+
+    pub fn is_source_file(&self, path: &Path) -> bool {
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("js") | Some("jsx") => true,
+            // Declaration files don't contain any code to annotate
+            Some("ts") | Some("tsx") => self.typescript && !file_name.ends_with(".d.ts"),
+            _ => false,
+        }
+    }
+
+    /// Excluded directories are pruned as a whole so we never descend into them
+    pub fn is_excluded(&self, relative_path: &Path) -> bool {
+        self.excludes.is_match(relative_path)
+    }
+
+    pub fn is_included(&self, relative_path: &Path) -> bool {
+        match &self.includes {
+            Some(includes) => includes.is_match(relative_path),
+            None => true,
+        }
+    }
+}
+
+fn build_glob_set<'a>(patterns: impl Iterator<Item = &'a str>) -> Result<GlobSet, globset::Error> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        builder.add(Glob::new(pattern)?);
+    }
+
+    builder.build()
+}
+
+/// Walks `root` and returns every `.js` and `.jsx` file that passes the filter, along with `.ts` and `.tsx` files if it accepts them.
+/// Paths listed in `.gitignore` and `.ignore` files or excluded by a `type-rs.toml` are skipped as well.
+pub fn find_source_files(
+    root: &Path,
+    filter: &FileFilter,
+    configs: &Arc<ConfigResolver>,
+) -> Traversal {
+    let excluded = Arc::new(Mutex::new(vec![]));
+    let walker = {
+        let root = root.to_path_buf();
+        let filter = filter.clone();
+        let configs = Arc::clone(configs);
+        let excluded = Arc::clone(&excluded);
+
+        WalkBuilder::new(&root)
+            .require_git(false)
+            .hidden(false)
+            .filter_entry(move |entry| {
+                if entry.depth() == 0 {
+                    return true;
+                }
+                if entry
+                    .file_type()
+                    .is_some_and(|file_type| file_type.is_dir())
+                    && SKIPPED_DIRECTORIES.contains(&entry.file_name().to_string_lossy().as_ref())
+                {
+                    return false;
+                }
+
+                let is_excluded = filter.is_excluded(&relative_to_root(&root, entry.path()))
+                    || match resolve_config(&configs, entry.path()) {
+                        Some((config, canonical_path)) => config.is_excluded(&canonical_path),
+                        None => true,
+                    };
+                let is_source = entry
+                    .file_type()
+                    .is_some_and(|file_type| file_type.is_dir())
+                    || filter.is_source_file(entry.path());
+                if is_excluded && is_source {
+                    debug!("Excluded {:?}", entry.path());
+                    excluded.lock().unwrap().push(entry.path().to_path_buf());
+                }
+
+                !is_excluded
+            })
+            .build()
+    };
+
+    let mut traversal = Traversal::default();
+    for entry in walker {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(err) => {
+                warn!("Unable to read directory entry: {err}");
+                continue;
+            }
+        };
+
+        if !entry
+            .file_type()
+            .is_some_and(|file_type| file_type.is_file())
+        {
+            debug!("Diving into new directory: {:?}", entry.path());
+            continue;
+        }
+
+        let path = entry.into_path();
+        if !filter.is_source_file(&path) {
+            continue;
+        }
+
+        let config = match resolve_config(configs, &path) {
+            Some((config, canonical_path)) => {
+                let included = filter.is_included(&relative_to_root(root, &path))
+                    && config.is_included(&canonical_path);
+                if !included {
+                    traversal.excluded.push(path);
+                    continue;
+                }
+
+                config
+            }
+            None => continue,
+        };
+
+        if let Some(file) = to_source_file(path, config) {
+            traversal.files.push(file);
+        }
+    }
+
+    traversal.excluded.append(&mut excluded.lock().unwrap());
+
+    traversal
+}
+
+/// Every file below `root`, including excluded and ignored ones, so the tree can be mirrored into `skip`.
+/// Version control and type-rs directories are left out, as is `skip` itself.
+pub fn find_all_files(root: &Path, skip: &Path) -> Vec<PathBuf> {
+    let skip = fs::canonicalize(skip).unwrap_or_else(|_| skip.to_path_buf());
+    let walker = WalkBuilder::new(root)
+        .standard_filters(false)
+        .filter_entry(move |entry| {
+            let is_dir = entry
+                .file_type()
+                .is_some_and(|file_type| file_type.is_dir());
+            !(is_dir
+                && (SKIPPED_DIRECTORIES.contains(&entry.file_name().to_string_lossy().as_ref())
+                    || fs::canonicalize(entry.path()).is_ok_and(|path| path == skip)))
+        })
+        .build();
+
+    walker
+        .filter_map(|entry| match entry {
+            Ok(entry) => Some(entry),
+            Err(err) => {
+                warn!("Unable to read directory entry: {err}");
+                None
+            }
+        })
+        .filter(|entry| {
+            entry
+                .file_type()
+                .is_some_and(|file_type| file_type.is_file())
+        })
+        .map(|entry| entry.into_path())
+        .collect()
+}
+
+fn relative_to_root(root: &Path, path: &Path) -> PathBuf {
+    match path.strip_prefix(root) {
+        Ok(relative) if relative.as_os_str().is_empty() => {
+            path.file_name().map(PathBuf::from).unwrap_or_default()
+        }
+        Ok(relative) => relative.to_path_buf(),
+        Err(_) => path.to_path_buf(),
+    }
+}
+
+/// Finds the configuration that applies to `path` along with the canonical path that its globs match against
+fn resolve_config(configs: &ConfigResolver, path: &Path) -> Option<(Arc<ResolvedConfig>, PathBuf)> {
+    let canonical_path = match fs::canonicalize(path) {
+        Ok(canonical_path) => canonical_path,
+        Err(err) => {
+            warn!("Unable to resolve {path:?}: {err}");
+            return None;
+        }
+    };
+
+    let directory = canonical_path.parent().unwrap_or(&canonical_path);
+    match configs.resolve(directory) {
+        Ok(config) => Some((config, canonical_path)),
+        Err(err) => {
+            error!("Skipping {path:?}: {err}");
+            None
+        }
+    }
+}
+
+fn to_source_file(path: PathBuf, config: Arc<ResolvedConfig>) -> Option<SourceFile> {
+    let file_name = path.file_stem()?.to_str()?.to_string();
+    let extension = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("js") | Some("ts") => "ts",
+        Some("jsx") | Some("tsx") => "tsx",
+        _ => return None,
+    };
+
+    Some(SourceFile {
+        path,
+        file_name,
+        extension,
+        config,
+    })
+}
@@ -1,13 +1,21 @@
 use log::{debug, trace};
 use rslint_parser::{
     ast::{
-        ArgList, AssignExpr, CallExpr, Declarator, DotExpr, Expr, ExprOrSpread, ExprStmt,
-        LiteralKind, NameRef, ObjectPattern, ObjectPatternProp, ParameterList,
+        ArgList, ArrowExprParams, AssignExpr, AssignPattern, AwaitExpr, BracketExpr, CallExpr,
+        Declarator, DotExpr, Expr, ExprOrSpread, ExprStmt, ForHead, ForOfStmt, LiteralKind,
+        NameRef, ObjectExpr, ObjectPattern, ObjectPatternProp, ObjectProp, ParameterList, Pattern,
+        PropName,
     },
     AstNode, SyntaxKind, SyntaxNode, SyntaxNodeExt,
 };
 use std::cmp::Ordering;
 
+/// The name of the field standing in for the properties collected by a rest element
+const INDEX_SIGNATURE: &str = "[key: string]";
+
+/// Calling these makes a parameter a promise rather than an object with a `then: Function` field
+const PROMISE_METHODS: [&str; 3] = ["then", "catch", "finally"];
+
 #[derive(Debug, Hash, PartialEq, Eq, Clone)]
 pub enum TypeDef {
     SimpleType(Option<Expr>),
@@ -18,6 +26,8 @@ pub enum TypeDef {
 pub struct TypeDefinition {
     pub name: String,
     pub ts_type: TypeDef,
+    /// Rendered as `name?:`
+    pub optional: bool,
 }
 
 impl TypeDefinition {
@@ -26,31 +36,38 @@ impl TypeDefinition {
         TypeDefinition {
             name,
             ts_type: TypeDef::SimpleType(expr),
+            optional: false,
         }
     }
 
-    fn render(&self, depth: usize) -> String {
+    fn render(&self, depth: usize, fallback_type: &str) -> String {
         let spacing = "    ".repeat(depth);
+        let optional = if self.optional { "?" } else { "" };
         let mut buf = String::from("");
 
         match &self.ts_type {
             TypeDef::SimpleType(expr) => {
-                let resolved_type = get_surrounding_expression(expr).unwrap_or_else(|| String::from("any"));
-                buf.push_str(format!("{spacing}{}: {},\n", self.name, resolved_type).as_str())
+                let resolved_type = get_surrounding_expression(expr, fallback_type)
+                    .unwrap_or_else(|| String::from(fallback_type));
+                buf.push_str(
+                    format!("{spacing}{}{optional}: {},\n", self.name, resolved_type).as_str(),
+                )
             }
             TypeDef::NestedType(children) => {
-                // Deterministic ordering of the children alphabetically
+                // Deterministic ordering of the children alphabetically, followed by the index signature
                 let mut sorted_children = children.clone();
-                sorted_children.sort_by(|a, b| a.name.cmp(&b.name));
+                sorted_children.sort_by(|a, b| {
+                    (a.name == INDEX_SIGNATURE, &a.name).cmp(&(b.name == INDEX_SIGNATURE, &b.name))
+                });
 
                 if depth == 0 {
                     for child in sorted_children {
-                        buf += child.render(depth + 1).as_str();
+                        buf += child.render(depth + 1, fallback_type).as_str();
                     }
                 } else {
-                    buf.push_str(format!("{spacing}{}: {{\n", self.name).as_str());
+                    buf.push_str(format!("{spacing}{}{optional}: {{\n", self.name).as_str());
                     for child in sorted_children {
-                        buf += child.render(depth + 1).as_str();
+                        buf += child.render(depth + 1, fallback_type).as_str();
                     }
                     buf.push_str(format!("{spacing}}},\n").as_str());
                 }
@@ -60,6 +77,24 @@ impl TypeDefinition {
         buf.clone()
     }
 
+    /// Number of fields `render` gives the fallback type, including arrays of it, because nothing better is known
+    pub fn fallback_fields(&self, fallback_type: &str) -> usize {
+        match &self.ts_type {
+            TypeDef::SimpleType(expr) => match get_surrounding_expression(expr, fallback_type) {
+                Some(resolved)
+                    if resolved != fallback_type && resolved != format!("{fallback_type}[]") =>
+                {
+                    0
+                }
+                _ => 1,
+            },
+            TypeDef::NestedType(children) => children
+                .iter()
+                .map(|child| child.fallback_fields(fallback_type))
+                .sum(),
+        }
+    }
+
     pub fn add_field(&mut self, new_type_def: &mut TypeDefinition) {
         match self.ts_type {
             TypeDef::SimpleType(_) => {
@@ -92,6 +127,13 @@ impl TypeDefinition {
         }
     }
 
+    fn has_field(&self, name: &str) -> bool {
+        match &self.ts_type {
+            TypeDef::SimpleType(_) => false,
+            TypeDef::NestedType(children) => children.iter().any(|child| child.name == name),
+        }
+    }
+
     fn get_children(&mut self) -> Option<&mut Vec<TypeDefinition>> {
         match self.ts_type {
             TypeDef::SimpleType(_) => None,
@@ -148,6 +190,7 @@ pub fn define_type_based_on_usage(
     let mut root_type = TypeDefinition {
         name: component_aspect.to_string(),
         ts_type: TypeDef::SimpleType(None),
+        optional: false,
     };
 
     for descendant in root.descendants() {
@@ -183,11 +226,7 @@ pub fn define_type_based_on_usage(
                     Some(Expr::NameRef(name_ref)) if name_ref.text() == component_aspect => {
                         debug!("name_ref! Found {:?}", name_ref);
 
-                        create_type_definition_structure(
-                            &mut root_type,
-                            &current_dot_expr,
-                            vec![],
-                        )
+                        create_type_definition_structure(&mut root_type, &current_dot_expr, vec![])
                     }
                     /*  Used in
                     ```
@@ -197,7 +236,10 @@ pub fn define_type_based_on_usage(
                     ```
                     */
                     Some(Expr::ThisExpr(this_expr)) => {
-                        let corresponding_name = this_expr.syntax().next_sibling().unwrap().text();
+                        let corresponding_name = match this_expr.syntax().next_sibling() {
+                            Some(sibling) => sibling.text(),
+                            None => continue,
+                        };
                         if corresponding_name == component_aspect {
                             debug!("this_expr! Found {:?}", this_expr);
 
@@ -240,6 +282,267 @@ pub fn define_type_based_on_usage(
     }
 }
 
+/// Array methods whose callback receives an element of the array as its first argument
+const ELEMENT_CALLBACK_METHODS: &[&str] = &[
+    "every",
+    "filter",
+    "find",
+    "findIndex",
+    "flatMap",
+    "forEach",
+    "map",
+    "some",
+];
+
+/// Like `define_type_based_on_usage`, but for the elements of the array `array_name`, such as a rest parameter.
+/// Elements are found by indexing (`args[0].id`), in callbacks (`args.forEach(arg => arg.name)`) and in `for...of` loops.
+pub fn define_element_type_based_on_usage(
+    root: &SyntaxNode,
+    array_name: &str,
+) -> Option<TypeDefinition> {
+    let mut element_type = TypeDefinition {
+        name: array_name.to_string(),
+        ts_type: TypeDef::SimpleType(None),
+        optional: false,
+    };
+
+    for descendant in root.descendants() {
+        match descendant.kind() {
+            SyntaxKind::BRACKET_EXPR => {
+                if !is_name_ref(descendant.to::<BracketExpr>().object(), array_name) {
+                    continue;
+                }
+
+                if let Some(parent) = descendant.parent().filter(|parent| parent.is::<DotExpr>()) {
+                    create_type_definition_structure(
+                        &mut element_type,
+                        &parent.to::<DotExpr>(),
+                        vec![],
+                    );
+                }
+            }
+            SyntaxKind::CALL_EXPR => {
+                let call = descendant.to::<CallExpr>();
+                let is_element_callback = match call.callee() {
+                    Some(Expr::DotExpr(dot_expr)) => {
+                        is_name_ref(dot_expr.object(), array_name)
+                            && dot_expr.prop().is_some_and(|method| {
+                                ELEMENT_CALLBACK_METHODS.contains(&method.text().as_str())
+                            })
+                    }
+                    _ => false,
+                };
+                if !is_element_callback {
+                    continue;
+                }
+
+                let callback = call
+                    .arguments()
+                    .and_then(|arguments| arguments.args().next());
+                if let Some((scope, element)) = callback.as_ref().and_then(first_parameter) {
+                    merge_usages(
+                        &mut element_type,
+                        define_type_based_on_usage(&scope, &element),
+                    );
+                }
+            }
+            SyntaxKind::FOR_OF_STMT => {
+                let for_of = descendant.to::<ForOfStmt>();
+                if !is_name_ref(for_of.right(), array_name) {
+                    continue;
+                }
+
+                let element = match for_of.left().and_then(|left| left.inner()) {
+                    Some(ForHead::Decl(declaration)) => declaration
+                        .declared()
+                        .next()
+                        .and_then(|declarator| declarator.pattern())
+                        .and_then(|pattern| pattern_name(&pattern)),
+                    _ => None,
+                };
+                if let (Some(element), Some(body)) = (element, for_of.cons()) {
+                    merge_usages(
+                        &mut element_type,
+                        define_type_based_on_usage(body.syntax(), &element),
+                    );
+                }
+            }
+            _ => (),
+        }
+    }
+
+    debug!("Resulting element type: {:?}", element_type);
+
+    match element_type.ts_type {
+        TypeDef::SimpleType(_) => None,
+        TypeDef::NestedType(_) => Some(element_type),
+    }
+}
+
+/// Combines the usages of a parameter with the object literal it defaults to, e.g. `function f(options = { size: 1 })`.
+/// Fields of the default keep the type of their value. Fields that are only used are optional since the default lacks them.
+pub fn define_type_based_on_default(
+    name: &str,
+    default: &ObjectExpr,
+    usages: Option<TypeDefinition>,
+) -> Option<TypeDefinition> {
+    let mut definition = define_type_from_object_literal(name, default)?;
+    if let Some(TypeDefinition {
+        ts_type: TypeDef::NestedType(fields),
+        ..
+    }) = usages
+    {
+        for mut field in fields {
+            field.optional = !definition.has_field(&field.name);
+            definition.add_field(&mut field);
+        }
+    }
+
+    match definition.ts_type {
+        TypeDef::SimpleType(_) => None,
+        TypeDef::NestedType(_) => Some(definition),
+    }
+}
+
+/// The interface of the object literals a function returns, e.g. `FooResult`. Fields missing from some of them are optional
+/// and fields whose type differs between them fall back.
+pub fn define_type_based_on_returns(name: &str, objects: &[ObjectExpr]) -> Option<TypeDefinition> {
+    let literals: Vec<TypeDefinition> = objects
+        .iter()
+        .map(|object| define_type_from_object_literal(name, object))
+        .collect::<Option<_>>()?;
+
+    let mut definition = TypeDefinition::new(name.to_string(), None);
+    for literal in &literals {
+        let fields = match &literal.ts_type {
+            TypeDef::SimpleType(_) => continue,
+            TypeDef::NestedType(fields) => fields,
+        };
+        for field in fields {
+            let mut field = TypeDefinition {
+                optional: !literals.iter().all(|other| other.has_field(&field.name)),
+                ..field.clone()
+            };
+            let existing = definition
+                .get_children()
+                .and_then(|children| children.iter_mut().find(|child| child.name == field.name));
+            match existing {
+                Some(existing) if !same_type(existing, &field) => {
+                    existing.ts_type = TypeDef::SimpleType(None)
+                }
+                _ => definition.add_field(&mut field),
+            }
+        }
+    }
+
+    match definition.ts_type {
+        TypeDef::SimpleType(_) => None,
+        TypeDef::NestedType(_) => Some(definition),
+    }
+}
+
+/// Nested types are merged rather than compared
+fn same_type(a: &TypeDefinition, b: &TypeDefinition) -> bool {
+    match (&a.ts_type, &b.ts_type) {
+        (TypeDef::SimpleType(a), TypeDef::SimpleType(b)) => {
+            get_type_from_expression(a, &None, "") == get_type_from_expression(b, &None, "")
+        }
+        (TypeDef::NestedType(_), TypeDef::NestedType(_)) => true,
+        _ => false,
+    }
+}
+
+/// `None` if the literal has fields we can't name or type: spreads, methods, accessors and computed keys
+fn define_type_from_object_literal(name: &str, object: &ObjectExpr) -> Option<TypeDefinition> {
+    let mut definition = TypeDefinition::new(name.to_string(), None);
+    for prop in object.props() {
+        let mut field = match prop {
+            ObjectProp::LiteralProp(literal) => {
+                let key = match literal.key()? {
+                    PropName::Computed(_) => return None,
+                    key => key.as_string()?,
+                };
+                match literal.value() {
+                    Some(Expr::ObjectExpr(nested)) if nested.props().next().is_some() => {
+                        define_type_from_object_literal(&key, &nested)?
+                    }
+                    // Other values are typed by how they are used, e.g. calls become `Function`
+                    value @ Some(Expr::Literal(_) | Expr::ArrayExpr(_)) => {
+                        TypeDefinition::new(key, value)
+                    }
+                    _ => TypeDefinition::new(key, None),
+                }
+            }
+            ObjectProp::IdentProp(ident) => TypeDefinition::new(ident.name()?.text(), None),
+            _ => return None,
+        };
+        definition.add_field(&mut field);
+    }
+
+    Some(definition)
+}
+
+/// Whether `name` is only ever awaited or has `then`, `catch` or `finally` called on it
+pub fn is_used_as_promise(root: &SyntaxNode, name: &str) -> bool {
+    let mut usages = root
+        .descendants()
+        .filter(|descendant| descendant.is::<NameRef>())
+        .filter(|descendant| descendant.to::<NameRef>().text() == name)
+        .peekable();
+
+    usages.peek().is_some()
+        && usages.all(|usage| match usage.parent() {
+            Some(parent) if parent.is::<AwaitExpr>() => true,
+            Some(parent) if parent.is::<DotExpr>() => {
+                let is_promise_method = parent
+                    .to::<DotExpr>()
+                    .prop()
+                    .is_some_and(|prop| PROMISE_METHODS.contains(&prop.text().as_str()));
+                is_promise_method && parent.parent().is_some_and(|call| call.is::<CallExpr>())
+            }
+            _ => false,
+        })
+}
+
+fn is_name_ref(expr: Option<Expr>, name: &str) -> bool {
+    matches!(expr, Some(Expr::NameRef(name_ref)) if name_ref.text() == name)
+}
+
+fn pattern_name(pattern: &Pattern) -> Option<String> {
+    match pattern {
+        Pattern::SinglePattern(single) => single.name().map(|name| name.text()),
+        _ => None,
+    }
+}
+
+/// The function and the name of its first parameter, if it's a plain identifier
+fn first_parameter(callback: &Expr) -> Option<(SyntaxNode, String)> {
+    let name = match callback {
+        Expr::ArrowExpr(arrow) => match arrow.params()? {
+            ArrowExprParams::Name(name) => Some(name.text()),
+            ArrowExprParams::ParameterList(parameters) => {
+                pattern_name(&parameters.parameters().next()?)
+            }
+        },
+        Expr::FnExpr(function) => pattern_name(&function.parameters()?.parameters().next()?),
+        _ => None,
+    }?;
+
+    Some((callback.syntax().clone(), name))
+}
+
+fn merge_usages(definition: &mut TypeDefinition, usages: Option<TypeDefinition>) {
+    if let Some(TypeDefinition {
+        ts_type: TypeDef::NestedType(fields),
+        ..
+    }) = usages
+    {
+        for mut field in fields {
+            definition.add_field(&mut field);
+        }
+    }
+}
+
 fn get_parent_dot_expr(expr: &DotExpr) -> Option<DotExpr> {
     let parent = expr.syntax().parent();
     if let Some(parent) = parent {
@@ -271,18 +574,40 @@ fn include_destructured_properties(expr: &Expr, new_type_def: &mut TypeDefinitio
             for element in object_pattern.elements() {
                 trace!("Object Pattern Element: {:?}", element.text());
                 match element {
-                    ObjectPatternProp::AssignPattern(_) => todo!(),
-                    ObjectPatternProp::KeyValuePattern(kv) => {
-                        if let Some(key) = kv.key() {
-                            new_type_def.add_field(&mut TypeDefinition::new(key.text(), None));
+                    ObjectPatternProp::AssignPattern(assign) => {
+                        if let Some(Pattern::SinglePattern(single)) = assign.key() {
+                            if let Some(name) = single.name() {
+                                new_type_def.add_field(&mut defaulted_field(name.text(), &assign));
+                            }
                         }
                     }
-                    ObjectPatternProp::RestPattern(_) => todo!(),
+                    ObjectPatternProp::KeyValuePattern(kv) => match kv.key() {
+                        // Any property may be read through a computed key, just like through a rest element
+                        Some(PropName::Computed(_)) => {
+                            new_type_def.add_field(&mut TypeDefinition::new(
+                                INDEX_SIGNATURE.to_string(),
+                                None,
+                            ));
+                        }
+                        Some(key) => {
+                            // `{ size: chosenSize = 'md' }`
+                            let mut field = match kv.syntax().child_with_ast::<AssignPattern>() {
+                                Some(assign) => defaulted_field(key.text(), &assign),
+                                None => TypeDefinition::new(key.text(), None),
+                            };
+                            new_type_def.add_field(&mut field);
+                        }
+                        None => {}
+                    },
+                    // Whatever else is passed ends up in the rest element
+                    ObjectPatternProp::RestPattern(_) => {
+                        new_type_def
+                            .add_field(&mut TypeDefinition::new(INDEX_SIGNATURE.to_string(), None));
+                    }
                     ObjectPatternProp::SinglePattern(single) => {
-                        new_type_def.add_field(&mut TypeDefinition::new(
-                            single.name().unwrap().text(),
-                            None,
-                        ));
+                        if let Some(name) = single.name() {
+                            new_type_def.add_field(&mut TypeDefinition::new(name.text(), None));
+                        }
                     }
                 }
             }
@@ -290,6 +615,14 @@ fn include_destructured_properties(expr: &Expr, new_type_def: &mut TypeDefinitio
     }
 }
 
+/// Destructured properties with a default value are typed from the default and may be left out
+fn defaulted_field(name: String, assign: &AssignPattern) -> TypeDefinition {
+    TypeDefinition {
+        optional: true,
+        ..TypeDefinition::new(name, assign.value())
+    }
+}
+
 fn create_type_definition_structure(
     parent_definition: &mut TypeDefinition,
     current_dot_expr: &DotExpr,
@@ -338,7 +671,7 @@ fn create_type_definition_structure(
     }
 }
 
-fn get_surrounding_expression(expr: &Option<Expr>) -> Option<String> {
+fn get_surrounding_expression(expr: &Option<Expr>, fallback_type: &str) -> Option<String> {
     debug!("Fetching expression: {expr:?}");
     match expr {
         Some(Expr::AssignExpr(assign)) => {
@@ -353,18 +686,20 @@ fn get_surrounding_expression(expr: &Option<Expr>) -> Option<String> {
                 let expr = expr.expr();
                 debug!("surrounding expression: {:?}", expr);
 
-                return get_type_from_expression(&expr, &None);
+                return get_type_from_expression(&expr, &None, fallback_type);
             }
 
             None
         }
-        _ => get_type_from_expression(expr, &None),
+        _ => get_type_from_expression(expr, &None, fallback_type),
     }
 }
 
+/// Resolves the type of `expr`, using `fallback_type` for values that don't tell us anything, such as `null`
 pub fn get_type_from_expression(
     expr: &Option<Expr>,
     created_type: &Option<String>,
+    fallback_type: &str,
 ) -> Option<String> {
     trace!("expr: {expr:?}");
     if created_type.is_some() {
@@ -373,40 +708,41 @@ pub fn get_type_from_expression(
 
     match expr {
         Some(Expr::ArrayExpr(array)) => {
-            let default_return = Some(String::from("any[]"));
+            let default_return = Some(format!("{fallback_type}[]"));
             let mut found_type = None;
             for element in array.elements() {
                 if let ExprOrSpread::Expr(expr) = element {
-                    let expression_type = get_type_from_expression(&Some(expr), created_type);
+                    let expression_type =
+                        get_type_from_expression(&Some(expr), created_type, fallback_type);
                     match expression_type {
                         Some(element_type) => {
                             match found_type {
                                 // FIXME: we can make this smarter by constructing a union type, e.g. `(string | number)[]`
-                                Some(t) if t != element_type => return default_return,
-                                _ => found_type = Some(format!("{}[]", element_type))
+                                Some(ref t) if *t != element_type => return default_return,
+                                _ => found_type = Some(element_type),
                             }
                         }
-                        None => return None
+                        None => return None,
                     }
                 }
             }
 
-            found_type.or(default_return)
-        },
-        Some(Expr::Literal(literal)) => {
-            match literal.kind() {
-                LiteralKind::Number(_) => Some(String::from("number")),
-                LiteralKind::BigInt(_) => Some(String::from("BigInt")),
-                LiteralKind::String => Some(String::from("string")),
-                LiteralKind::Null => Some(String::from("any")),
-                LiteralKind::Bool(_) => Some(String::from("boolean")),
-                LiteralKind::Regex => Some(String::from("RegExp")),
-            }
+            found_type
+                .map(|element_type| format!("{element_type}[]"))
+                .or(default_return)
         }
-        Some(Expr::ObjectExpr(_)) | None => Some(String::from("any")),
-        Some(Expr::NameRef(nr)) if nr.text() == "undefined" => Some(String::from("any")), 
+        Some(Expr::Literal(literal)) => match literal.kind() {
+            LiteralKind::Number(_) => Some(String::from("number")),
+            LiteralKind::BigInt(_) => Some(String::from("BigInt")),
+            LiteralKind::String => Some(String::from("string")),
+            LiteralKind::Null => Some(String::from(fallback_type)),
+            LiteralKind::Bool(_) => Some(String::from("boolean")),
+            LiteralKind::Regex => Some(String::from("RegExp")),
+        },
+        Some(Expr::ObjectExpr(_)) | None => Some(String::from(fallback_type)),
+        Some(Expr::NameRef(nr)) if nr.text() == "undefined" => Some(String::from(fallback_type)),
         Some(Expr::AssignExpr(assign_expr)) => {
-            get_type_from_expression(&assign_expr.rhs(), created_type)
+            get_type_from_expression(&assign_expr.rhs(), created_type, fallback_type)
         }
         // FIXME: use more specific function signatures
         Some(Expr::CallExpr(call_expr)) => {
@@ -415,38 +751,36 @@ pub fn get_type_from_expression(
             }
 
             Some(String::from("Function"))
-        },
-        _ => None
-
-        // Expr::ArrowExpr(_) => todo!(),
-        // Expr::Template(_) => todo!(),
-        // Expr::ThisExpr(_) => todo!(),
-        // Expr::ObjectExpr(_) => todo!(),
-        // Expr::GroupingExpr(_) => todo!(),
-        // Expr::BracketExpr(_) => todo!(),
-        // Expr::DotExpr(_) => todo!(),
-        // Expr::UnaryExpr(_) => todo!(),
-        // Expr::BinExpr(_) => todo!(),
-        // Expr::CondExpr(_) => todo!(),
-        // Expr::SequenceExpr(_) => todo!(),
-        // Expr::FnExpr(_) => todo!(),
-        // Expr::ClassExpr(_) => todo!(),
-        // Expr::NewTarget(_) => todo!(),
-        // Expr::ImportMeta(_) => todo!(),
-        // Expr::SuperCall(_) => todo!(),
-        // Expr::ImportCall(_) => todo!(),
-        // Expr::YieldExpr(_) => todo!(),
-        // Expr::AwaitExpr(_) => todo!(),
-        // Expr::PrivatePropAccess(_) => todo!(),
-        // Expr::TsNonNull(_) => todo!(),
-        // Expr::TsAssertion(_) => todo!(),
-        // Expr::TsConstAssertion(_) => todo!(),
+        }
+        _ => None, // Expr::ArrowExpr(_) => todo!(),
+                   // Expr::Template(_) => todo!(),
+                   // Expr::ThisExpr(_) => todo!(),
+                   // Expr::ObjectExpr(_) => todo!(),
+                   // Expr::GroupingExpr(_) => todo!(),
+                   // Expr::BracketExpr(_) => todo!(),
+                   // Expr::DotExpr(_) => todo!(),
+                   // Expr::UnaryExpr(_) => todo!(),
+                   // Expr::BinExpr(_) => todo!(),
+                   // Expr::CondExpr(_) => todo!(),
+                   // Expr::SequenceExpr(_) => todo!(),
+                   // Expr::FnExpr(_) => todo!(),
+                   // Expr::ClassExpr(_) => todo!(),
+                   // Expr::NewTarget(_) => todo!(),
+                   // Expr::ImportMeta(_) => todo!(),
+                   // Expr::SuperCall(_) => todo!(),
+                   // Expr::ImportCall(_) => todo!(),
+                   // Expr::YieldExpr(_) => todo!(),
+                   // Expr::AwaitExpr(_) => todo!(),
+                   // Expr::PrivatePropAccess(_) => todo!(),
+                   // Expr::TsNonNull(_) => todo!(),
+                   // Expr::TsAssertion(_) => todo!(),
+                   // Expr::TsConstAssertion(_) => todo!(),
     }
 }
 
-pub fn create_type_definition(def: &TypeDefinition, name: &str) -> String {
+pub fn create_type_definition(def: &TypeDefinition, name: &str, fallback_type: &str) -> String {
     debug!("Type definition: {def:?}");
-    let definition = def.render(0);
+    let definition = def.render(0, fallback_type);
 
     format!(
         "
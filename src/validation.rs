@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Range;
+
+use log::debug;
+use rslint_parser::{parse_with_syntax, Syntax};
+
+use crate::error::TypeRsError;
+
+/// A syntax error reported by rslint
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntaxError {
+    pub message: String,
+    /// Byte offsets in the parsed source
+    pub span: Range<usize>,
+    /// 1-based line of the start of the span
+    pub line: usize,
+}
+
+impl fmt::Display for SyntaxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} (line {}, at {:?})",
+            self.message, self.line, self.span
+        )
+    }
+}
+
+/// Parses `source` as TypeScript and returns its syntax errors
+pub fn syntax_errors(source: &str) -> Vec<SyntaxError> {
+    let parse = parse_with_syntax(source, 0, Syntax::default().typescript());
+    parse
+        .errors()
+        .iter()
+        .map(|diagnostic| {
+            let span = diagnostic
+                .primary
+                .as_ref()
+                .map(|primary| primary.span.range.clone())
+                .unwrap_or_default();
+            let line = source[..span.start.min(source.len())].matches('\n').count() + 1;
+
+            SyntaxError {
+                message: diagnostic.title.clone(),
+                span,
+                line,
+            }
+        })
+        .collect()
+}
+
+/// Makes sure `add_types` didn't break the file by comparing the syntax errors of `converted` with those of `original`.
+/// Errors are matched by their message since the inserted annotations shift every span after them.
+/// Errors the input already had, for example because rslint doesn't understand JSX, are tolerated.
+pub fn validate_output(original: &str, converted: &str) -> Result<(), TypeRsError> {
+    let mut existing: HashMap<String, usize> = HashMap::new();
+    for error in syntax_errors(original) {
+        *existing.entry(error.message).or_default() += 1;
+    }
+
+    let introduced: Vec<SyntaxError> = syntax_errors(converted)
+        .into_iter()
+        .filter(|error| match existing.get_mut(&error.message) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                false
+            }
+            _ => true,
+        })
+        .collect();
+
+    if introduced.is_empty() {
+        return Ok(());
+    }
+
+    debug!("The conversion introduced syntax errors: {introduced:?}");
+    Err(TypeRsError::InvalidOutput { errors: introduced })
+}
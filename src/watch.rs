@@ -0,0 +1,83 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::time::Duration;
+
+use log::{debug, warn};
+use notify::{EventKind, RecursiveMode, Watcher};
+
+use crate::config::ConfigResolver;
+use crate::error::TypeRsError;
+use crate::traversal::{find_source_files, FileFilter, SourceFile};
+
+/// Editors tend to save a file in several steps, so we wait for things to settle before converting anything
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Watches `root` and calls `on_change` with every batch of `.js` and `.jsx` files that are added or modified.
+/// Files are filtered exactly like a normal run, including `.gitignore` and the configuration files.
+/// Only returns if the watcher fails.
+pub fn watch_source_files<F>(
+    root: &Path,
+    filter: &FileFilter,
+    configs: &Arc<ConfigResolver>,
+    mut on_change: F,
+) -> Result<(), TypeRsError>
+where
+    F: FnMut(Vec<SourceFile>),
+{
+    // Converted files are written as TypeScript, watching those too would make us convert our own output again
+    let filter = filter.clone().with_typescript(false);
+
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender)?;
+    watcher.watch(root, RecursiveMode::Recursive)?;
+
+    loop {
+        let mut changed = HashSet::new();
+        collect_changes(receiver.recv().map_err(|_| disconnected())?, &mut changed);
+        loop {
+            match receiver.recv_timeout(DEBOUNCE) {
+                Ok(event) => collect_changes(event, &mut changed),
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => return Err(disconnected()),
+            }
+        }
+
+        let changed: HashSet<PathBuf> = changed
+            .into_iter()
+            .filter(|path| filter.is_source_file(path))
+            .filter_map(|path| fs::canonicalize(path).ok())
+            .collect();
+        if changed.is_empty() {
+            continue;
+        }
+
+        debug!("Changed files: {changed:?}");
+        // Walking the tree again is cheap compared to converting and keeps the filtering identical to a normal run
+        let files: Vec<SourceFile> = find_source_files(root, &filter, configs)
+            .files
+            .into_iter()
+            .filter(|file| fs::canonicalize(&file.path).is_ok_and(|path| changed.contains(&path)))
+            .collect();
+
+        if !files.is_empty() {
+            on_change(files);
+        }
+    }
+}
+
+fn collect_changes(event: notify::Result<notify::Event>, changed: &mut HashSet<PathBuf>) {
+    match event {
+        Ok(event) if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) => {
+            changed.extend(event.paths)
+        }
+        Ok(_) => {}
+        Err(err) => warn!("Error while watching for changes: {err}"),
+    }
+}
+
+fn disconnected() -> TypeRsError {
+    TypeRsError::Watch(notify::Error::generic("the watcher stopped unexpectedly"))
+}
@@ -0,0 +1,74 @@
+mod common;
+
+mod tests {
+    use crate::common::{create_directory, type_rs};
+    use pretty_assertions::assert_eq;
+    use std::fs;
+    use type_rs::cache::{Cache, CACHE_DIRECTORY};
+    use type_rs::config::Strictness;
+    use type_rs::parser::{ConversionOptions, ConversionSummary};
+
+    #[test]
+    fn cache_key_depends_on_contents_and_options() {
+        let loose = ConversionOptions::default();
+        let strict = ConversionOptions {
+            strictness: Strictness::Strict,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            Cache::key("function f(a) {}", &loose),
+            Cache::key("function f(a) {}", &loose)
+        );
+        assert_ne!(
+            Cache::key("function f(a) {}", &loose),
+            Cache::key("function f(b) {}", &loose)
+        );
+        assert_ne!(
+            Cache::key("function f(a) {}", &loose),
+            Cache::key("function f(a) {}", &strict)
+        );
+    }
+
+    #[test]
+    fn cache_is_only_written_when_converting() {
+        let directory = create_directory("cache", "modes");
+        fs::write(directory.join("a.js"), "function f(a) {}").unwrap();
+
+        type_rs(&directory, &["--dry-run", "."]);
+        type_rs(&directory, &["--diff", "."]);
+        assert!(!directory.join(CACHE_DIRECTORY).exists());
+
+        type_rs(&directory, &["."]);
+        assert!(directory.join(CACHE_DIRECTORY).is_dir());
+    }
+
+    #[test]
+    fn cache_returns_inserted_entry() {
+        let cache = Cache::in_directory(&create_directory("cache", "insert"));
+        let key = Cache::key("function f(a) {}", &ConversionOptions::default());
+        let summary = ConversionSummary {
+            parameters: 1,
+            any_annotations: 1,
+            ..Default::default()
+        };
+
+        assert_eq!(cache.get(&key), None);
+        cache.insert(&key, "function f(a: any) {}", &summary);
+        assert_eq!(
+            cache.get(&key),
+            Some((String::from("function f(a: any) {}"), summary))
+        );
+    }
+
+    #[test]
+    fn cache_ignores_invalid_entry() {
+        let directory = create_directory("cache", "invalid");
+        let cache = Cache::new(&directory);
+        let key = Cache::key("function f(a) {}", &ConversionOptions::default());
+        fs::create_dir_all(directory.join(&key[..2])).unwrap();
+        fs::write(directory.join(&key[..2]).join(format!("{key}.json")), "{").unwrap();
+
+        assert_eq!(cache.get(&key), None);
+    }
+}
@@ -0,0 +1,40 @@
+// Every test crate includes this module but only uses some of it
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// An empty directory for one test, `module` and `name` keep test runs from sharing files
+pub fn create_directory(module: &str, name: &str) -> PathBuf {
+    let directory =
+        std::env::temp_dir().join(format!("type-rs-{module}-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&directory);
+    fs::create_dir_all(&directory).unwrap();
+    fs::canonicalize(directory).unwrap()
+}
+
+/// Like `create_directory`, filled with files and their contents
+pub fn create_tree(module: &str, name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let directory = create_directory(module, name);
+    for (file, contents) in files {
+        let path = directory.join(file);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+    directory
+}
+
+/// Runs the binary in `directory` and fails the test if it doesn't succeed
+pub fn type_rs(directory: &Path, args: &[&str]) {
+    let output = Command::new(env!("CARGO_BIN_EXE_type-rs"))
+        .current_dir(directory)
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "type-rs {args:?}: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
@@ -0,0 +1,129 @@
+mod common;
+
+mod tests {
+    use crate::common::create_directory;
+    use pretty_assertions::assert_eq;
+    use std::fs;
+    use std::path::Path;
+    use type_rs::config::{Config, ConfigResolver, FallbackType, Naming, OutputFormat, Strictness};
+
+    #[test]
+    fn config_from_toml() {
+        let config = Config::from_toml(
+            Path::new("type-rs.toml"),
+            r#"
+exclude = ["generated/**"]
+strictness = "strict"
+naming = "prefixed"
+output = "dry-run"
+
+[rules]
+catch-clauses = false
+return-types = true
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.exclude, vec!["generated/**".to_string()]);
+        assert_eq!(config.strictness, Some(Strictness::Strict));
+        assert_eq!(config.naming, Some(Naming::Prefixed));
+        assert_eq!(config.output, Some(OutputFormat::DryRun));
+        assert_eq!(config.rules.catch_clauses, Some(false));
+        assert_eq!(config.rules.return_types, Some(true));
+        assert_eq!(config.rules.parameters, None);
+    }
+
+    #[test]
+    fn config_from_toml_unknown_field() {
+        assert!(Config::from_toml(Path::new("type-rs.toml"), "strict = true").is_err());
+    }
+
+    #[test]
+    fn config_from_package_json() {
+        let config = Config::from_package_json(
+            Path::new("package.json"),
+            r#"{ "name": "app", "type-rs": { "naming": "prefixed" } }"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.unwrap().naming, Some(Naming::Prefixed));
+    }
+
+    #[test]
+    fn config_from_package_json_without_entry() {
+        let config = Config::from_package_json(Path::new("package.json"), r#"{ "name": "app" }"#);
+
+        assert_eq!(config.unwrap(), None);
+    }
+
+    #[test]
+    fn config_resolver_nested_overrides_parent() {
+        let root = create_directory("config", "nested");
+        let nested = root.join("packages").join("app");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(
+            root.join("type-rs.toml"),
+            "strictness = \"strict\"\nnaming = \"prefixed\"\nexclude = [\"legacy/**\"]",
+        )
+        .unwrap();
+        fs::write(
+            nested.join("package.json"),
+            r#"{ "type-rs": { "strictness": "loose", "exclude": ["*.test.js"] } }"#,
+        )
+        .unwrap();
+
+        let resolver = ConfigResolver::new();
+        let root_config = resolver.resolve(&root).unwrap();
+        let nested_config = resolver.resolve(&nested).unwrap();
+
+        assert_eq!(root_config.config.strictness, Some(Strictness::Strict));
+        assert_eq!(nested_config.config.strictness, Some(Strictness::Loose));
+        assert_eq!(nested_config.config.naming, Some(Naming::Prefixed));
+
+        assert!(root_config.is_excluded(&root.join("legacy/index.js")));
+        assert!(!root_config.is_excluded(&nested.join("index.test.js")));
+        assert!(nested_config.is_excluded(&nested.join("index.test.js")));
+        assert!(!nested_config.is_excluded(&nested.join("index.js")));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn config_resolver_overrides_win() {
+        let root = create_directory("config", "overrides");
+        fs::write(
+            root.join("type-rs.toml"),
+            "strictness = \"strict\"\nfallback-type = \"any\"\ncatch-clause-type = \"unknown\"",
+        )
+        .unwrap();
+
+        let config = ConfigResolver::new().resolve(&root).unwrap();
+        assert_eq!(
+            config.conversion_options().fallback_type(),
+            FallbackType::Any
+        );
+        assert_eq!(
+            config.conversion_options().catch_clause_type(),
+            FallbackType::Unknown
+        );
+
+        let config = ConfigResolver::new()
+            .with_overrides(Config {
+                fallback_type: Some(FallbackType::None),
+                ..Config::default()
+            })
+            .resolve(&root)
+            .unwrap();
+        assert_eq!(
+            config.conversion_options().fallback_type(),
+            FallbackType::None
+        );
+        assert_eq!(
+            config.conversion_options().catch_clause_type(),
+            FallbackType::Unknown
+        );
+        assert_eq!(config.config.strictness, Some(Strictness::Strict));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
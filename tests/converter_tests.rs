@@ -0,0 +1,129 @@
+mod tests {
+    use pretty_assertions::assert_eq;
+    use type_rs::config::{Naming, Strictness};
+    use type_rs::converter::{Converter, Diagnostic, Edit, JsxHandling, Severity};
+    use type_rs::parser::InferenceRules;
+
+    #[test]
+    fn convert_reports_edits() {
+        let result = Converter::default()
+            .convert("function test(options, b) {\n    options.enabled = true;\n}")
+            .unwrap();
+
+        assert_eq!(
+            result.source,
+            "\ninterface Options {\n    enabled: boolean,\n}\nfunction test(options: Options, b: any): void {\n    options.enabled = true;\n}"
+        );
+        assert_eq!(
+            result.edits,
+            vec![
+                Edit {
+                    offset: 0,
+                    span: 0..45,
+                    text: String::from("\ninterface Options {\n    enabled: boolean,\n}\n"),
+                },
+                Edit {
+                    offset: 21,
+                    span: 66..75,
+                    text: String::from(": Options"),
+                },
+                Edit {
+                    offset: 24,
+                    span: 78..83,
+                    text: String::from(": any"),
+                },
+                Edit {
+                    offset: 25,
+                    span: 84..90,
+                    text: String::from(": void"),
+                },
+            ]
+        );
+        for edit in &result.edits {
+            assert_eq!(&result.source[edit.span.clone()], edit.text);
+        }
+        assert_eq!(result.extension, "ts");
+        assert_eq!(result.summary.parameters, 2);
+    }
+
+    #[test]
+    fn convert_returns_type_definitions() {
+        let result = Converter::default()
+            .with_naming(Naming::Prefixed)
+            .convert("function test(options) {\n    options.enabled = true;\n}")
+            .unwrap();
+
+        let names: Vec<&str> = result
+            .type_definitions
+            .iter()
+            .map(|definition| definition.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["IOptions"]);
+    }
+
+    #[test]
+    fn convert_reports_fallbacks() {
+        let result = Converter::default()
+            .with_strictness(Strictness::Strict)
+            .convert("try {} catch (e) {}")
+            .unwrap();
+
+        assert_eq!(result.source, "try {} catch (e: unknown) {}");
+        assert_eq!(
+            result.diagnostics,
+            vec![Diagnostic {
+                severity: Severity::Information,
+                message: String::from("Nothing better than `unknown` could be inferred for `e`"),
+                span: 14..15,
+            }]
+        );
+    }
+
+    #[test]
+    fn convert_with_rules() {
+        let result = Converter::default()
+            .with_rules(InferenceRules {
+                catch_clauses: false,
+                ..InferenceRules::default()
+            })
+            .convert("function f(a) {}\ntry {} catch (e) {}")
+            .unwrap();
+
+        assert_eq!(
+            result.source,
+            "function f(a: any): void {}\ntry {} catch (e) {}"
+        );
+    }
+
+    #[test]
+    fn convert_accepts_computed_destructuring() {
+        let result = Converter::default()
+            .convert("function f(a) { const { [key]: value } = a; }")
+            .unwrap();
+
+        assert_eq!(
+            result.source,
+            "\ninterface A {\n    [key: string]: any,\n}\nfunction f(a: A): void { const { [key]: value } = a; }"
+        );
+    }
+
+    #[test]
+    fn target_extension_follows_jsx_handling() {
+        let source = "import React from 'react';";
+
+        assert_eq!(Converter::default().target_extension(source), "tsx");
+        assert_eq!(Converter::default().target_extension(""), "ts");
+        assert_eq!(
+            Converter::default()
+                .with_jsx(JsxHandling::Tsx)
+                .target_extension(""),
+            "tsx"
+        );
+        assert_eq!(
+            Converter::default()
+                .with_jsx(JsxHandling::TypeScript)
+                .target_extension(source),
+            "ts"
+        );
+    }
+}
@@ -0,0 +1,65 @@
+mod tests {
+    use pretty_assertions::assert_eq;
+    use std::path::Path;
+    use type_rs::diff::create_unified_diff;
+
+    #[test]
+    fn create_unified_diff_renames_and_changes() {
+        let diff = create_unified_diff(
+            Path::new("./src/foo.js"),
+            Path::new("./src/foo.ts"),
+            "function foo(a) {}\n",
+            "function foo(a: any) {}\n",
+        );
+
+        assert_eq!(
+            diff,
+            "diff --git a/src/foo.js b/src/foo.ts
+rename from src/foo.js
+rename to src/foo.ts
+--- a/src/foo.js
++++ b/src/foo.ts
+@@ -1 +1 @@
+-function foo(a) {}
++function foo(a: any) {}
+"
+        );
+    }
+
+    #[test]
+    fn create_unified_diff_rename_only() {
+        let diff = create_unified_diff(
+            Path::new("foo.js"),
+            Path::new("foo.ts"),
+            "const a = 5;\n",
+            "const a = 5;\n",
+        );
+
+        assert_eq!(
+            diff,
+            "diff --git a/foo.js b/foo.ts
+rename from foo.js
+rename to foo.ts
+"
+        );
+    }
+
+    #[test]
+    fn create_unified_diff_absolute_paths() {
+        let directory = std::env::current_dir().unwrap();
+        let diff = create_unified_diff(
+            &directory.join("src/foo.js"),
+            &directory.join("src/foo.ts"),
+            "const a = 5;\n",
+            "const a = 5;\n",
+        );
+
+        assert_eq!(
+            diff,
+            "diff --git a/src/foo.js b/src/foo.ts
+rename from src/foo.js
+rename to src/foo.ts
+"
+        );
+    }
+}
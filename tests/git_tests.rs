@@ -0,0 +1,136 @@
+mod common;
+
+mod tests {
+    use crate::common::{create_directory, type_rs};
+    use pretty_assertions::assert_eq;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use std::process::Command;
+    use type_rs::git;
+
+    fn create_repository(name: &str) -> PathBuf {
+        let directory = create_directory("git", name);
+
+        git(&directory, &["init", "--quiet"]);
+        git(&directory, &["config", "user.name", "type-rs"]);
+        git(&directory, &["config", "user.email", "type-rs@example.com"]);
+        git(&directory, &["config", "commit.gpgsign", "false"]);
+        directory
+    }
+
+    fn git(repository: &Path, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(repository)
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "git {args:?}: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8(output.stdout).unwrap()
+    }
+
+    #[test]
+    fn commit_only_includes_paths() {
+        let repository = create_repository("commit");
+        fs::write(repository.join("a.js"), "a").unwrap();
+        fs::write(repository.join("b.js"), "b").unwrap();
+        git(&repository, &["add", "a.js", "b.js"]);
+        git(&repository, &["commit", "--quiet", "-m", "Initial commit"]);
+
+        git::move_file(
+            &repository,
+            &repository.join("a.js"),
+            &repository.join("a.ts"),
+        )
+        .unwrap();
+        fs::write(repository.join("b.js"), "changed").unwrap();
+        git::commit(
+            &repository,
+            "Rename",
+            &[repository.join("a.js"), repository.join("a.ts")],
+        )
+        .unwrap();
+
+        assert_eq!(
+            git(
+                &repository,
+                &["show", "--name-status", "--format=%s", "HEAD"]
+            ),
+            "Rename\n\nR100\ta.js\ta.ts\n"
+        );
+        assert_eq!(git(&repository, &["status", "--porcelain"]), " M b.js\n");
+    }
+
+    #[test]
+    fn convert_with_git_commits_renames_and_annotations() {
+        let repository = create_repository("convert");
+        fs::create_dir_all(repository.join("src")).unwrap();
+        fs::write(repository.join("src/a.js"), "function f(a) {}").unwrap();
+        fs::write(repository.join("src/b.js"), "const b = 1;").unwrap();
+        git(&repository, &["add", "src"]);
+        git(&repository, &["commit", "--quiet", "-m", "Initial commit"]);
+
+        type_rs(&repository, &["--git", "--no-cache", "src"]);
+
+        assert_eq!(
+            git(&repository, &["log", "--format=%s"]),
+            "Add type annotations\nRename JavaScript files to TypeScript\nInitial commit\n"
+        );
+        assert_eq!(
+            git(
+                &repository,
+                &["show", "--name-status", "--format=", "HEAD~1"]
+            ),
+            "R100\tsrc/a.js\tsrc/a.ts\nR100\tsrc/b.js\tsrc/b.ts\n"
+        );
+        assert_eq!(
+            git(&repository, &["show", "--name-status", "--format=", "HEAD"]),
+            "M\tsrc/a.ts\n"
+        );
+        assert_eq!(
+            fs::read_to_string(repository.join("src/a.ts")).unwrap(),
+            "function f(a: any): void {}"
+        );
+    }
+
+    #[test]
+    fn convert_with_git_reports_failed_annotation_commit() {
+        let repository = create_repository("failed-commit");
+        fs::write(repository.join("a.js"), "function f(a) {}").unwrap();
+        git(&repository, &["add", "a.js"]);
+        git(&repository, &["commit", "--quiet", "-m", "Initial commit"]);
+
+        // Only the second commit is rejected, after the rename was committed and the file rewritten
+        let hook = repository.join(".git/hooks/commit-msg");
+        fs::write(
+            &hook,
+            "#!/bin/sh\n! grep -q 'Add type annotations' \"$1\"\n",
+        )
+        .unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&hook, fs::Permissions::from_mode(0o755)).unwrap();
+        }
+
+        type_rs(
+            &repository,
+            &["--git", "--no-cache", "--report", "report.json", "."],
+        );
+
+        let report: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(repository.join("report.json")).unwrap())
+                .unwrap();
+        let file = &report.as_array().unwrap()[0];
+        assert_eq!(file["status"], "failed");
+        assert!(file["error"].as_str().unwrap().contains("git failed"));
+        assert_eq!(
+            git(&repository, &["log", "--format=%s"]),
+            "Rename JavaScript files to TypeScript\nInitial commit\n"
+        );
+    }
+}
@@ -0,0 +1,123 @@
+mod common;
+
+mod tests {
+    use crate::common::{create_directory, type_rs};
+    use pretty_assertions::assert_eq;
+    use std::fs;
+    use type_rs::journal::{
+        read_journal, rollback, write_atomic, Journal, JournalEntry, JOURNAL_DIRECTORY,
+    };
+
+    #[test]
+    fn journal_is_only_created_when_used() {
+        let directory = create_directory("journal", "unused");
+        let journal = Journal::in_directory(&directory);
+
+        assert!(!journal.is_used());
+        assert!(!journal.path().exists());
+    }
+
+    #[test]
+    fn unchanged_typescript_files_are_not_rewritten() {
+        let directory = create_directory("journal", "unchanged");
+        fs::write(directory.join("a.ts"), "function f(a: any): void {}").unwrap();
+
+        type_rs(&directory, &["--typescript", "--no-cache", "."]);
+
+        assert_eq!(
+            fs::read_to_string(directory.join("a.ts")).unwrap(),
+            "function f(a: any): void {}"
+        );
+        assert!(!directory.join(JOURNAL_DIRECTORY).exists());
+    }
+
+    #[test]
+    fn journal_records_changes() {
+        let directory = create_directory("journal", "records");
+        fs::write(directory.join("a.js"), "function f(a) {}").unwrap();
+        let journal = Journal::new(&directory.join("journal.jsonl"));
+
+        journal.create_dir_all(&directory.join("out/src")).unwrap();
+        journal
+            .write_file(&directory.join("out/src/a.ts"), b"function f(a: any) {}")
+            .unwrap();
+        journal.remove_file(&directory.join("a.js")).unwrap();
+
+        assert_eq!(
+            read_journal(journal.path()).unwrap(),
+            vec![
+                JournalEntry::CreateDirectory {
+                    path: directory.join("out"),
+                },
+                JournalEntry::CreateDirectory {
+                    path: directory.join("out/src"),
+                },
+                JournalEntry::CreateFile {
+                    path: directory.join("out/src/a.ts"),
+                },
+                JournalEntry::ReplaceFile {
+                    path: directory.join("a.js"),
+                    backup: directory.join("journal/1"),
+                },
+            ]
+        );
+        assert_eq!(
+            fs::read_to_string(directory.join("journal/1")).unwrap(),
+            "function f(a) {}"
+        );
+    }
+
+    #[test]
+    fn rollback_restores_previous_tree() {
+        let directory = create_directory("journal", "rollback");
+        fs::write(directory.join("a.js"), "function f(a) {}").unwrap();
+        fs::write(directory.join("b.ts"), "old").unwrap();
+        let journal = Journal::new(&directory.join("journal.jsonl"));
+
+        journal
+            .write_file(&directory.join("a.ts"), b"function f(a: any) {}")
+            .unwrap();
+        journal.remove_file(&directory.join("a.js")).unwrap();
+        journal.write_file(&directory.join("b.ts"), b"new").unwrap();
+        journal.create_dir_all(&directory.join("out")).unwrap();
+
+        assert_eq!(rollback(journal.path()).unwrap(), 4);
+        assert_eq!(
+            fs::read_to_string(directory.join("a.js")).unwrap(),
+            "function f(a) {}"
+        );
+        assert_eq!(fs::read_to_string(directory.join("b.ts")).unwrap(), "old");
+        assert!(!directory.join("a.ts").exists());
+        assert!(!directory.join("out").exists());
+    }
+
+    #[test]
+    fn rollback_ignores_incomplete_last_entry() {
+        let directory = create_directory("journal", "incomplete");
+        let journal_path = directory.join("journal.jsonl");
+        fs::write(directory.join("a.ts"), "").unwrap();
+        fs::write(
+            &journal_path,
+            format!(
+                "{{\"action\":\"create_file\",\"path\":{:?}}}\n{{\"action\":\"create_fi",
+                directory.join("a.ts")
+            ),
+        )
+        .unwrap();
+
+        assert_eq!(rollback(&journal_path).unwrap(), 1);
+        assert!(!directory.join("a.ts").exists());
+    }
+
+    #[test]
+    fn write_atomic_replaces_file() {
+        let directory = create_directory("journal", "atomic");
+        let path = directory.join("a.ts");
+        fs::write(&path, "old").unwrap();
+
+        write_atomic(&path, b"new").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+        assert_eq!(fs::read_dir(&directory).unwrap().count(), 1);
+    }
+}
@@ -0,0 +1,113 @@
+mod tests {
+    use lsp_types::{
+        CodeActionOrCommand, DocumentChangeOperation, DocumentChanges, Position, Range, Url,
+    };
+    use pretty_assertions::assert_eq;
+    use type_rs::lsp::{code_actions, offset_to_position, position_to_offset};
+    use type_rs::parser::ConversionOptions;
+
+    fn titles(actions: &[CodeActionOrCommand]) -> Vec<String> {
+        actions
+            .iter()
+            .map(|action| match action {
+                CodeActionOrCommand::CodeAction(action) => action.title.clone(),
+                CodeActionOrCommand::Command(command) => command.title.clone(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn positions_count_utf16_code_units() {
+        let text = "const a = '😀';\nfunction f(b) {}";
+
+        assert_eq!(offset_to_position(text, 0), Position::new(0, 0));
+        assert_eq!(offset_to_position(text, 18), Position::new(1, 0));
+        assert_eq!(offset_to_position(text, 15), Position::new(0, 13));
+        assert_eq!(position_to_offset(text, Position::new(0, 13)), 15);
+        assert_eq!(position_to_offset(text, Position::new(1, 11)), 29);
+        assert_eq!(position_to_offset(text, Position::new(5, 0)), text.len());
+    }
+
+    #[test]
+    fn code_actions_for_cursor() {
+        let uri = Url::parse("file:///project/src/a.js").unwrap();
+        let text =
+            "function test(options, b) {\n    options.enabled = true;\n    try {} catch (e) {}\n}";
+        let cursor = Position::new(0, 24);
+
+        let actions = code_actions(
+            &uri,
+            text,
+            Range::new(cursor, cursor),
+            &ConversionOptions::default(),
+        );
+
+        assert_eq!(
+            titles(&actions),
+            vec![
+                "Annotate the return type of `test`",
+                "Annotate parameter `b`",
+                "Convert file to TypeScript"
+            ]
+        );
+    }
+
+    #[test]
+    fn code_actions_convert_file_renames_it() {
+        let uri = Url::parse("file:///project/src/a.js").unwrap();
+        let cursor = Position::new(0, 0);
+
+        let actions = code_actions(
+            &uri,
+            "function f(a) {}",
+            Range::new(cursor, cursor),
+            &ConversionOptions::default(),
+        );
+
+        let operations = match actions.last() {
+            Some(CodeActionOrCommand::CodeAction(action)) => {
+                match &action.edit.as_ref().unwrap().document_changes {
+                    Some(DocumentChanges::Operations(operations)) => operations.clone(),
+                    changes => panic!("Unexpected changes {changes:?}"),
+                }
+            }
+            action => panic!("Unexpected action {action:?}"),
+        };
+        assert_eq!(operations.len(), 2);
+        assert!(matches!(
+            &operations[1],
+            DocumentChangeOperation::Op(lsp_types::ResourceOp::Rename(rename))
+                if rename.new_uri.as_str() == "file:///project/src/a.ts"
+        ));
+    }
+
+    #[test]
+    fn code_actions_without_suggestions() {
+        let uri = Url::parse("file:///project/src/a.ts").unwrap();
+        let cursor = Position::new(0, 0);
+
+        let actions = code_actions(
+            &uri,
+            "function f(a: string): void {}",
+            Range::new(cursor, cursor),
+            &ConversionOptions::default(),
+        );
+
+        assert!(actions.is_empty());
+    }
+
+    #[test]
+    fn code_actions_skip_invalid_conversion() {
+        let uri = Url::parse("file:///project/src/a.ts").unwrap();
+        let cursor = Position::new(0, 11);
+
+        let actions = code_actions(
+            &uri,
+            "function f(a?) {}",
+            Range::new(cursor, cursor),
+            &ConversionOptions::default(),
+        );
+
+        assert!(!titles(&actions).contains(&String::from("Convert file to TypeScript")));
+    }
+}
@@ -0,0 +1,142 @@
+mod tests {
+    use std::path::Path;
+    use structopt::clap::ErrorKind;
+    use structopt::StructOpt;
+    use type_rs::config::FallbackType;
+    use type_rs::converter::{Converter, JsxHandling};
+    use type_rs::options::{Command, Options};
+
+    #[test]
+    fn options_path() {
+        let options = Options::from_iter_safe(["type-rs", "src", "--dry-run"]).unwrap();
+
+        assert_eq!(options.path(), Path::new("src"));
+        assert!(options.dry_run);
+        assert!(options.command.is_none());
+    }
+
+    #[test]
+    fn options_rollback() {
+        let options = Options::from_iter_safe(["type-rs", "rollback", "journal.jsonl"]).unwrap();
+
+        assert!(matches!(
+            options.command,
+            Some(Command::Rollback { journal }) if journal == Path::new("journal.jsonl")
+        ));
+    }
+
+    #[test]
+    fn options_watch_with_global_flags() {
+        let options = Options::from_iter_safe([
+            "type-rs",
+            "watch",
+            "src",
+            "--include",
+            "app/**",
+            "--no-cache",
+        ])
+        .unwrap();
+
+        assert_eq!(options.path(), Path::new("src"));
+        assert_eq!(options.include, vec![String::from("app/**")]);
+        assert!(options.no_cache);
+    }
+
+    #[test]
+    fn options_stats() {
+        let options = Options::from_iter_safe(["type-rs", "stats", "src", "--json"]).unwrap();
+
+        assert_eq!(options.path(), Path::new("src"));
+        assert!(matches!(
+            options.command,
+            Some(Command::Stats { json: true, .. })
+        ));
+    }
+
+    #[test]
+    fn options_config_overrides() {
+        let options =
+            Options::from_iter_safe(["type-rs", "src", "--fallback-type", "none"]).unwrap();
+        let overrides = options.config_overrides();
+
+        assert_eq!(overrides.fallback_type, Some(FallbackType::None));
+        assert_eq!(overrides.catch_clause_type, None);
+        assert!(
+            Options::from_iter_safe(["type-rs", "src", "--catch-clause-type", "never"]).is_err()
+        );
+    }
+
+    #[test]
+    fn options_validate_stdin_flags() {
+        let validate = |args: &[&str]| {
+            Options::from_iter_safe(args)
+                .unwrap()
+                .validate()
+                .map_err(|err| err.kind)
+        };
+
+        assert_eq!(
+            validate(&["type-rs", "-", "--jsx", "--filename", "src/a.jsx"]),
+            Ok(())
+        );
+        assert_eq!(validate(&["type-rs", "src"]), Ok(()));
+        assert_eq!(
+            validate(&["type-rs"]),
+            Err(ErrorKind::MissingRequiredArgument)
+        );
+        assert_eq!(
+            validate(&["type-rs", "-", "--git"]),
+            Err(ErrorKind::ArgumentConflict)
+        );
+        assert_eq!(
+            validate(&["type-rs", "-", "--dry-run"]),
+            Err(ErrorKind::ArgumentConflict)
+        );
+        assert_eq!(
+            validate(&["type-rs", "-", "--out-dir", "out"]),
+            Err(ErrorKind::ArgumentConflict)
+        );
+        assert_eq!(
+            validate(&["type-rs", "src", "--jsx"]),
+            Err(ErrorKind::ArgumentConflict)
+        );
+        assert_eq!(
+            validate(&["type-rs", "src", "--filename", "a.jsx"]),
+            Err(ErrorKind::ArgumentConflict)
+        );
+    }
+
+    #[test]
+    fn options_stdin_jsx_handling() {
+        let jsx_handling =
+            |args: &[&str]| Options::from_iter_safe(args).unwrap().stdin_jsx_handling();
+
+        assert_eq!(jsx_handling(&["type-rs", "-"]), JsxHandling::Detect);
+        assert_eq!(jsx_handling(&["type-rs", "-", "--jsx"]), JsxHandling::Tsx);
+        assert_eq!(
+            jsx_handling(&["type-rs", "-", "--filename", "src/a.js"]),
+            JsxHandling::Detect
+        );
+        assert_eq!(
+            jsx_handling(&["type-rs", "-", "--filename", "src/a.jsx"]),
+            JsxHandling::Tsx
+        );
+        assert_eq!(
+            jsx_handling(&["type-rs", "-", "--filename", "src/a.tsx"]),
+            JsxHandling::Tsx
+        );
+    }
+
+    #[test]
+    fn converter_detects_tsx_from_filename() {
+        let options =
+            Options::from_iter_safe(["type-rs", "-", "--filename", "src/button.jsx"]).unwrap();
+        let converter = Converter::default().with_jsx(options.stdin_jsx_handling());
+
+        assert_eq!(converter.target_extension("const a = 1;"), "tsx");
+        assert_eq!(
+            converter.convert("function f(a) {}").unwrap().extension,
+            "tsx"
+        );
+    }
+}
@@ -0,0 +1,74 @@
+mod common;
+
+mod tests {
+    use crate::common::{create_tree, type_rs};
+    use pretty_assertions::assert_eq;
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn read(path: PathBuf) -> String {
+        fs::read_to_string(path).unwrap()
+    }
+
+    #[test]
+    fn out_dir_mirrors_converted_files() {
+        let root = create_tree(
+            "out-dir",
+            "converted",
+            &[("src/app/a.js", "function f(a) {}")],
+        );
+
+        type_rs(&root, &["--no-cache", "--out-dir", "out", "src"]);
+
+        assert_eq!(
+            read(root.join("out/app/a.ts")),
+            "function f(a: any): void {}"
+        );
+        assert_eq!(read(root.join("src/app/a.js")), "function f(a) {}");
+    }
+
+    #[test]
+    fn copy_untouched_copies_excluded_and_ignored_files() {
+        let root = create_tree(
+            "out-dir",
+            "untouched",
+            &[
+                ("src/a.js", "function f(a) {}"),
+                ("src/styles.css", "body {}"),
+                ("src/node_modules/dep/index.js", "module.exports = 1;"),
+                ("src/.gitignore", "local.js\n"),
+                ("src/local.js", "const local = 1;"),
+            ],
+        );
+
+        type_rs(
+            &root,
+            &["--no-cache", "--out-dir", "out", "--copy-untouched", "src"],
+        );
+
+        assert_eq!(read(root.join("out/a.ts")), "function f(a: any): void {}");
+        assert!(!root.join("out/a.js").exists());
+        assert_eq!(read(root.join("out/styles.css")), "body {}");
+        assert_eq!(
+            read(root.join("out/node_modules/dep/index.js")),
+            "module.exports = 1;"
+        );
+        assert_eq!(read(root.join("out/local.js")), "const local = 1;");
+    }
+
+    #[test]
+    fn out_dir_overrides_configured_output() {
+        let root = create_tree(
+            "out-dir",
+            "configured-output",
+            &[
+                ("src/a.js", "function f(a) {}"),
+                ("src/type-rs.toml", "output = \"dry-run\"\n"),
+            ],
+        );
+
+        type_rs(&root, &["--no-cache", "--out-dir", "out", "src"]);
+
+        assert_eq!(read(root.join("out/a.ts")), "function f(a: any): void {}");
+    }
+}
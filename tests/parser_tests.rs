@@ -7,46 +7,60 @@ fn init() {
 
 mod tests {
     use pretty_assertions::assert_eq;
-    use type_rs::parser::add_types;
+    use type_rs::config::{FallbackType, Naming, Strictness};
+    use type_rs::parser::{
+        add_types, add_types_with_options, add_types_with_summary, suggest_types,
+        ConversionOptions, ConversionSummary, InferenceRules, Suggestion,
+    };
+    use type_rs::text_editor::Change;
 
     fn compare(input: &str, expected_output: &str) {
-        let output = add_types(String::from(input));
+        let output = add_types(String::from(input)).unwrap();
         assert_eq!(output, expected_output);
     }
 
     #[test]
     fn add_types_function() {
-        compare("function foo(a) {}", "function foo(a: any) {}");
+        compare("function foo(a) {}", "function foo(a: any): void {}");
     }
 
     #[test]
     fn add_types_function_default_value_number() {
-        compare("function foo(a = 5) {}", "function foo(a: number = 5) {}");
+        compare(
+            "function foo(a = 5) {}",
+            "function foo(a: number = 5): void {}",
+        );
     }
 
     #[test]
     fn add_types_function_default_value_string() {
         compare(
             "function foo(a = \"hey\") {}",
-            "function foo(a: string = \"hey\") {}",
+            "function foo(a: string = \"hey\"): void {}",
         );
     }
 
     #[test]
     fn add_types_function_default_value_object() {
-        compare("function foo(a = {}) {}", "function foo(a: any = {}) {}");
+        compare(
+            "function foo(a = {}) {}",
+            "function foo(a: any = {}): void {}",
+        );
     }
 
     #[test]
     fn add_types_function_default_value_array() {
-        compare("function foo(a = []) {}", "function foo(a: any[] = []) {}");
+        compare(
+            "function foo(a = []) {}",
+            "function foo(a: any[] = []): void {}",
+        );
     }
 
     #[test]
     fn add_types_function_default_value_array_string() {
         compare(
             "function foo(a = [\"s1\"]) {}",
-            "function foo(a: string[] = [\"s1\"]) {}",
+            "function foo(a: string[] = [\"s1\"]): void {}",
         );
     }
 
@@ -54,7 +68,7 @@ mod tests {
     fn add_types_function_default_value_array_number() {
         compare(
             "function foo(a = [1]) {}",
-            "function foo(a: number[] = [1]) {}",
+            "function foo(a: number[] = [1]): void {}",
         );
     }
 
@@ -62,7 +76,7 @@ mod tests {
     fn add_types_function_default_value_array_mixed() {
         compare(
             "function foo(a = [\"s1\", 1]) {}",
-            "function foo(a: any[] = [\"s1\", 1]) {}",
+            "function foo(a: any[] = [\"s1\", 1]): void {}",
         );
     }
 
@@ -70,7 +84,7 @@ mod tests {
     fn add_types_function_default_value_array_mixed_null() {
         compare(
             "function foo(a = [1, null]) {}",
-            "function foo(a: any[] = [1, null]) {}",
+            "function foo(a: any[] = [1, null]): void {}",
         );
     }
 
@@ -78,7 +92,7 @@ mod tests {
     fn add_types_function_default_value_null() {
         compare(
             "function foo(a = null) {}",
-            "function foo(a: any = null) {}",
+            "function foo(a: any = null): void {}",
         );
     }
 
@@ -86,7 +100,7 @@ mod tests {
     fn add_types_function_default_value_undefined() {
         compare(
             "function foo(a = undefined) {}",
-            "function foo(a: any = undefined) {}",
+            "function foo(a: any = undefined): void {}",
         );
     }
 
@@ -94,7 +108,7 @@ mod tests {
     fn add_types_function_default_value_regex() {
         compare(
             "function foo(a = /.*/) {}",
-            "function foo(a: RegExp = /.*/) {}",
+            "function foo(a: RegExp = /.*/): void {}",
         );
     }
 
@@ -102,7 +116,7 @@ mod tests {
     fn add_types_function_default_value_bigint_suffix() {
         compare(
             "function foo(a = 9007199254740991n) {}",
-            "function foo(a: BigInt = 9007199254740991n) {}",
+            "function foo(a: BigInt = 9007199254740991n): void {}",
         );
     }
 
@@ -110,7 +124,7 @@ mod tests {
     fn add_types_function_default_value_bigint_ctor() {
         compare(
             "function foo(a = BigInt(9007199254740991)) {}",
-            "function foo(a: BigInt = BigInt(9007199254740991)) {}",
+            "function foo(a: BigInt = BigInt(9007199254740991)): void {}",
         );
     }
 
@@ -118,7 +132,7 @@ mod tests {
     fn add_types_function_default_value_bool() {
         compare(
             "function foo(a = true) {}",
-            "function foo(a: boolean = true) {}",
+            "function foo(a: boolean = true): void {}",
         );
     }
 
@@ -126,7 +140,7 @@ mod tests {
     fn add_types_function_default_value_date() {
         compare(
             "function foo(a = new Date()) {}",
-            "function foo(a = new Date()) {}",
+            "function foo(a = new Date()): void {}",
         );
     }
 
@@ -134,7 +148,7 @@ mod tests {
     fn add_types_function_multi_param() {
         compare(
             "function foo(a, b, c) {}",
-            "function foo(a: any, b: any, c: any) {}",
+            "function foo(a: any, b: any, c: any): void {}",
         );
     }
 
@@ -154,7 +168,7 @@ function foo(
 function foo(
     a: any,
     b: any,
-    c: any) 
+    c: any): void 
 {
     /* hello comments */
     console.log(test);
@@ -166,7 +180,49 @@ function foo(
     fn add_types_preexisting_type() {
         compare(
             "function foo(a: string[] = []) {}",
-            "function foo(a: string[] = []) {}",
+            "function foo(a: string[] = []): void {}",
+        );
+    }
+
+    #[test]
+    fn add_types_typescript_only_fills_gaps() {
+        compare(
+            "function foo(a: Options, b, c: number = 1, ...rest: string[]) {
+    a.enabled = true;
+    try {} catch (e: unknown) {}
+    try {} catch (f) {}
+    let x: string;
+    let y;
+}",
+            "function foo(a: Options, b: any, c: number = 1, ...rest: string[]): void {
+    a.enabled = true;
+    try {} catch (e: unknown) {}
+    try {} catch (f: any) {}
+    let x: string;
+    let y: any;
+}",
+        );
+    }
+
+    #[test]
+    fn add_types_reuses_declared_interface() {
+        compare(
+            "
+interface Options {
+    enabled: boolean,
+}
+
+function test(options) {
+    options.enabled = true;
+}",
+            "
+interface Options {
+    enabled: boolean,
+}
+
+function test(options: Options): void {
+    options.enabled = true;
+}",
         );
     }
 
@@ -179,7 +235,7 @@ function foo(
     fn add_types_destructured_parameter() {
         compare(
             "function getRole({ permissions, user }) { }",
-            "function getRole({ permissions, user }: any) { }",
+            "function getRole({ permissions, user }: any): void { }",
         );
     }
 
@@ -193,7 +249,7 @@ function foo() {
     }
 }",
             "
-function foo() {          
+function foo(): void {          
     for (const key in {}) {
 
     }
@@ -205,7 +261,7 @@ function foo() {
     fn add_types_export_default_function() {
         compare(
             "export default function foo(route) { }",
-            "export default function foo(route: any) { }",
+            "export default function foo(route: any): void { }",
         );
     }
 
@@ -218,7 +274,7 @@ class ColorPicker {
 }",
             "
 class ColorPicker {
-  componentDidUpdate(prevProps: any, prevState: any) { }
+  componentDidUpdate(prevProps: any, prevState: any): void { }
 }",
         );
     }
@@ -227,7 +283,7 @@ class ColorPicker {
     fn add_types_const_arrow_function() {
         compare(
             "const mapStateToProps = (state, props) => { }",
-            "const mapStateToProps = (state: any, props: any) => { }",
+            "const mapStateToProps = (state: any, props: any): void => { }",
         );
     }
 
@@ -235,7 +291,7 @@ class ColorPicker {
     fn add_types_lambda() {
         compare(
             "function foo() { sources.filter((v, k) => true; }",
-            "function foo() { sources.filter((v: any, k: any) => true; }",
+            "function foo(): void { sources.filter((v: any, k: any): boolean => true; }",
         );
     }
 
@@ -249,9 +305,13 @@ function foo() {
   };
 };",
             "
-function foo() {
+interface FooResult {
+    bar: any,
+}
+
+function foo(): FooResult {
   return {
-    bar: (a: any, b: any) => {},
+    bar: (a: any, b: any): void => {},
   };
 };",
         );
@@ -317,6 +377,22 @@ function foo() {
         );
     }
 
+    #[test]
+    fn add_types_class_getter_setter() {
+        compare(
+            "class C { get x() { return 1; } set x(v) { this.v = v; } }",
+            "class C { get x() { return 1; } set x(v: any) { this.v = v; } }",
+        );
+    }
+
+    #[test]
+    fn add_types_object_getter_setter() {
+        compare(
+            "const o = { get x() { return 1; }, set x(v) {} };",
+            "const o = { get x() { return 1; }, set x(v: any) {} };",
+        );
+    }
+
     #[test]
     fn add_types_try_catch() {
         compare(
@@ -402,7 +478,7 @@ interface Props {
 }
 
 class MyComponent extends Component<Props> { 
-    function test() {
+    function test(): void {
         console.log(this.props.wowee);
     }
 }",
@@ -447,14 +523,14 @@ class MyComponent extends Component<Props, State> {
         this.state = {};
     }
 
-    function test() {
+    function test(): void {
         console.log(this.props.wowee);
         this.props.callback();
         this.props.callback();
         this.state.testNumber = 5;
     }
 
-    render() {
+    render(): null | undefined {
         if (this.props.otherone === 5) {
             return null;
         }
@@ -481,7 +557,7 @@ interface State {
 }
 
 class MyComponent extends Component<Props, State> { 
-    function test() {
+    function test(): void {
         console.log(this.state.wowee);
     }
 }",
@@ -504,7 +580,7 @@ interface Props {
 }
 
 class MyComponent extends Component<Props> { 
-    function test() {
+    function test(): void {
         const { somefield } = this.props;
         console.log(somefield);
     }
@@ -529,7 +605,7 @@ interface Props {
 }
 
 class MyComponent extends Component<Props> { 
-    function test() {
+    function test(): void {
         const { somefield } = this.props;
         const { abc } = this.props;
     }
@@ -556,7 +632,7 @@ interface Props {
 }
 
 class MyComponent extends Component<Props> { 
-    function test() {
+    function test(): void {
         const { somefield } = this.props;
         const { abc } = this.props.deep;
     }
@@ -586,7 +662,7 @@ interface Props {
 }
 
 class MyComponent extends Component<Props> { 
-    function test() {
+    function test(): void {
         const { somefield } = this.props.a.b.c;
         console.log(somefield);
     }
@@ -610,7 +686,7 @@ interface Props {
 }
 
 class MyComponent extends Component<Props> { 
-    function test() {
+    function test(): void {
         const { somefield: alias } = this.props;
         console.log(alias);
     }
@@ -635,7 +711,7 @@ interface Props {
 }
 
 class MyComponent extends Component<Props> { 
-    function test() {
+    function test(): void {
         const { somefield, otherField } = this.props;
         console.log(somefield);
     }
@@ -661,7 +737,7 @@ interface Props {
 }
 
 class MyComponent extends Component<Props> { 
-    function test() {
+    function test(): void {
         const { somefield } = this.props;
         console.log(this.props.somefield.wowee)
     }
@@ -682,7 +758,7 @@ interface Props {
     name: any,
 }
 
-function Welcome(props: Props) {
+function Welcome(props: Props): void {
     console.log(props.name);
     return undefined;
 }",
@@ -702,7 +778,7 @@ interface Args {
     name: any,
 }
 
-function Welcome(args: Args) {
+function Welcome(args: Args): void {
     console.log(args.name);
     return undefined;
 }",
@@ -722,7 +798,7 @@ interface B {
     field: any,
 }
 
-function foo(a: any, b: B, c: any) {
+function foo(a: any, b: B, c: any): void {
     console.log(a);
     console.log(b.field);
 }",
@@ -744,7 +820,7 @@ interface B {
     otherfield: any,
 }
 
-function foo(a: any, b: B, c: any) {
+function foo(a: any, b: B, c: any): void {
     console.log(a);
     console.log(b.field);
     console.log(b.otherfield);
@@ -766,7 +842,7 @@ interface A {
     },
 }
 
-function foo(a: A) {
+function foo(a: A): void {
     a.sa.nestedlongname();
 }",
         );
@@ -785,7 +861,7 @@ interface B {
     field: number,
 }
 
-function foo(a: any, b: B, c: any) {
+function foo(a: any, b: B, c: any): void {
     console.log(a);
     b.field = 5;
 }",
@@ -805,7 +881,7 @@ interface B {
     field: string,
 }
 
-function foo(a: any, b: B, c: any) {
+function foo(a: any, b: B, c: any): void {
     console.log(a);
     b.field = \"hello\";
 }",
@@ -825,7 +901,7 @@ interface B {
     callableField: Function,
 }
 
-function foo(a: any, b: B, c: any) {
+function foo(a: any, b: B, c: any): void {
     console.log(a);
     b.callableField();
 }",
@@ -849,7 +925,7 @@ interface B {
     callableField: Function,
 }
 
-function foo(a: any, b: B, c: any) {
+function foo(a: any, b: B, c: any): void {
     console.log(a);
     b.callableField(doThing(c));
 }
@@ -872,7 +948,7 @@ interface State {
     player: any,
 }
 
-const mapStateToProps = (state: State) => {
+const mapStateToProps = (state: State): void => {
     const { player } = state;   
 }",
         );
@@ -898,9 +974,9 @@ interface Action3 {
     data: any,
 }
 
-function first(action: Action) { console.log(action.data); }
-function second(action: Action2) { console.log(action.data); }
-function third(action: Action3) { console.log(action.data); }",
+function first(action: Action): void { console.log(action.data); }
+function second(action: Action2): void { console.log(action.data); }
+function third(action: Action3): void { console.log(action.data); }",
         );
     }
 
@@ -920,7 +996,7 @@ interface A {
     },
 }
 
-function test(a: A) {
+function test(a: A): void {
     a.nest.more.field = 5;    
 }",
         );
@@ -944,7 +1020,7 @@ interface A {
     },
 }
 
-function test(a: A) {
+function test(a: A): void {
     console.log(a.nest.more.field.toString());
 }",
         );
@@ -966,9 +1042,696 @@ interface A {
     },
 }
 
-function test(a: A) {
+function test(a: A): void {
     a.field = { test: 5 };
 }",
         );
     }
+
+    #[test]
+    fn add_types_with_summary_counts_changes() {
+        let (_, summary) = add_types_with_summary(String::from(
+            "
+function test(a, b) {
+    a.field();
+    try {} catch (e) {}
+}",
+        ))
+        .unwrap();
+
+        assert_eq!(
+            summary,
+            ConversionSummary {
+                parameters: 2,
+                catch_clauses: 1,
+                return_types: 1,
+                interfaces: vec![String::from("A")],
+                any_annotations: 2,
+                ..Default::default()
+            }
+        );
+        assert_eq!(summary.annotations(), 4);
+    }
+
+    #[test]
+    fn add_types_with_summary_counts_fallback_fields() {
+        let (output, summary) = add_types_with_summary(String::from(
+            "
+function test(a) {
+    console.log(a.name, a.tags[0]);
+    a.count = 5;
+}",
+        ))
+        .unwrap();
+
+        assert_eq!(
+            output,
+            "
+interface A {
+    count: number,
+    name: any,
+    tags: any,
+}
+
+function test(a: A): void {
+    console.log(a.name, a.tags[0]);
+    a.count = 5;
+}"
+        );
+        assert_eq!(summary.any_annotations, 2);
+    }
+
+    #[test]
+    fn add_types_with_options_strict() {
+        let options = ConversionOptions {
+            strictness: Strictness::Strict,
+            ..Default::default()
+        };
+        let (output, _) = add_types_with_options(
+            String::from("function foo(a, b = null) { try {} catch (e) {} }"),
+            &options,
+        )
+        .unwrap();
+
+        assert_eq!(
+            output,
+            "function foo(a: unknown, b: unknown = null): void { try {} catch (e: unknown) {} }"
+        );
+    }
+
+    #[test]
+    fn add_types_with_options_fallback_none() {
+        let options = ConversionOptions {
+            fallback_type: Some(FallbackType::None),
+            ..Default::default()
+        };
+        let (output, summary) = add_types_with_options(
+            String::from("function foo(a, b = 1) { let c; let d = []; try {} catch (e) {} }"),
+            &options,
+        )
+        .unwrap();
+
+        assert_eq!(
+            output,
+            "function foo(a, b: number = 1): void { let c; let d = []; try {} catch (e) {} }"
+        );
+        assert_eq!(summary.any_annotations, 0);
+    }
+
+    #[test]
+    fn add_types_with_options_catch_clause_type() {
+        let options = ConversionOptions {
+            catch_clause_type: Some(FallbackType::Unknown),
+            ..Default::default()
+        };
+        let (output, _) = add_types_with_options(
+            String::from("function foo(a) { try {} catch (e) {} }"),
+            &options,
+        )
+        .unwrap();
+
+        assert_eq!(
+            output,
+            "function foo(a: any): void { try {} catch (e: unknown) {} }"
+        );
+    }
+
+    #[test]
+    fn add_types_with_options_prefixed_naming() {
+        let options = ConversionOptions {
+            naming: Naming::Prefixed,
+            ..Default::default()
+        };
+        let (output, _) = add_types_with_options(
+            String::from(
+                "
+function test(options) {
+    options.enabled = true;
+}",
+            ),
+            &options,
+        )
+        .unwrap();
+
+        assert_eq!(
+            output,
+            "
+interface IOptions {
+    enabled: boolean,
+}
+
+function test(options: IOptions): void {
+    options.enabled = true;
+}"
+        );
+    }
+
+    #[test]
+    fn add_types_return_type_union() {
+        compare(
+            "
+function foo(a) {
+    if (a) return 1;
+    return 'one';
+}
+function bar() {}
+const baz = () => [1, 2];",
+            "
+function foo(a: any): number | string {
+    if (a) return 1;
+    return 'one';
+}
+function bar(): void {}
+const baz = (): number[] => [1, 2];",
+        );
+    }
+
+    #[test]
+    fn add_types_return_type_falls_through() {
+        compare(
+            "
+function foo(a) {
+    if (a) return 1;
+}
+function bar(a) {
+    if (a) {
+        return 1;
+    } else {
+        return 2;
+    }
+}
+function baz(a) {
+    switch (a) {
+        case 1:
+        case 2:
+            return 'low';
+        default:
+            throw new Error();
+    }
+}
+function qux(a) {
+    switch (a) {
+        case 1:
+            break;
+        default:
+            return 1;
+    }
+}
+function quux(a) {
+    try {
+        return 1;
+    } catch (e) {
+        return 2;
+    }
+}",
+            "
+function foo(a: any): number | undefined {
+    if (a) return 1;
+}
+function bar(a: any): number {
+    if (a) {
+        return 1;
+    } else {
+        return 2;
+    }
+}
+function baz(a: any): string {
+    switch (a) {
+        case 1:
+        case 2:
+            return 'low';
+        default:
+            throw new Error();
+    }
+}
+function qux(a: any): number | undefined {
+    switch (a) {
+        case 1:
+            break;
+        default:
+            return 1;
+    }
+}
+function quux(a: any): number {
+    try {
+        return 1;
+    } catch (e: any) {
+        return 2;
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn add_types_return_type_object_literal() {
+        compare(
+            "
+function foo(a) {
+    if (a) {
+        return { id: 1, name: 'a' };
+    }
+    return { id: 2 };
+}
+const bar = () => ({ enabled: true });
+const f = () => ({ id: 1 });",
+            "
+interface FooResult {
+    id: number,
+    name?: string,
+}
+
+interface BarResult {
+    enabled: boolean,
+}
+
+interface FResult {
+    id: number,
+}
+
+function foo(a: any): FooResult {
+    if (a) {
+        return { id: 1, name: 'a' };
+    }
+    return { id: 2 };
+}
+const bar = (): BarResult => ({ enabled: true });
+const f = (): FResult => ({ id: 1 });",
+        );
+    }
+
+    #[test]
+    fn add_types_return_type_skips_spread() {
+        compare(
+            "function f(a) { return { ...a, b: 1 }; }",
+            "function f(a: any) { return { ...a, b: 1 }; }",
+        );
+    }
+
+    #[test]
+    fn add_types_return_type_skips_method_and_computed_key() {
+        compare(
+            "function f(k) { return { m() {}, [k]: 2, c: 3 }; }",
+            "function f(k: any) { return { m(): void {}, [k]: 2, c: 3 }; }",
+        );
+    }
+
+    #[test]
+    fn add_types_return_type_skips_accessors() {
+        compare(
+            "function f() { return { get x() { return 1; }, set x(v) {} }; }",
+            "function f() { return { get x() { return 1; }, set x(v: any) {} }; }",
+        );
+    }
+
+    #[test]
+    fn add_types_return_type_skips_nested_spread() {
+        compare(
+            "function f(b) { return { a: { ...b } }; }",
+            "function f(b: any) { return { a: { ...b } }; }",
+        );
+    }
+
+    #[test]
+    fn add_types_return_type_async() {
+        compare(
+            "
+async function foo(a) {
+    if (a) return 1;
+}
+const bar = async () => ({ id: 1 });",
+            "
+interface BarResult {
+    id: number,
+}
+
+async function foo(a: any): Promise<number | undefined> {
+    if (a) return 1;
+}
+const bar = async (): Promise<BarResult> => ({ id: 1 });",
+        );
+    }
+
+    #[test]
+    fn add_types_return_type_async_and_generator_follow_branches() {
+        compare(
+            "
+function s(x) { switch (x) { case 1: return 1; default: return 2; } }
+async function t(x) {
+    try {
+        return 1;
+    } catch (e) {
+        throw e;
+    }
+}
+function* u(x) {
+    yield 1;
+    if (x) {
+        return 'a';
+    } else {
+        return 'b';
+    }
+}",
+            "
+function s(x: any): number { switch (x) { case 1: return 1; default: return 2; } }
+async function t(x: any): Promise<number> {
+    try {
+        return 1;
+    } catch (e: any) {
+        throw e;
+    }
+}
+function* u(x: any): Generator<number, string, any> {
+    yield 1;
+    if (x) {
+        return 'a';
+    } else {
+        return 'b';
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn add_types_return_type_generator() {
+        compare(
+            "
+function* foo() {
+    yield 1;
+    yield 2;
+    return 'done';
+}
+async function* bar() { yield 'a'; }
+function* baz() { yield* foo(); }",
+            "
+function* foo(): Generator<number, string, any> {
+    yield 1;
+    yield 2;
+    return 'done';
+}
+async function* bar(): AsyncGenerator<string, void, any> { yield 'a'; }
+function* baz() { yield* foo(); }",
+        );
+    }
+
+    #[test]
+    fn add_types_return_type_skips_unknown_values() {
+        compare(
+            "
+function foo(a) {
+    function bar() { return null; }
+    return bar();
+}",
+            "
+function foo(a: any) {
+    function bar(): null { return null; }
+    return bar();
+}",
+        );
+    }
+
+    #[test]
+    fn add_types_with_options_disabled_rules() {
+        let options = ConversionOptions {
+            rules: InferenceRules {
+                catch_clauses: false,
+                interfaces: false,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let (output, _) = add_types_with_options(
+            String::from("function foo(a) { a.b(); try {} catch (e) {} }"),
+            &options,
+        )
+        .unwrap();
+
+        assert_eq!(
+            output,
+            "function foo(a: any): void { a.b(); try {} catch (e) {} }"
+        );
+    }
+
+    #[test]
+    fn add_types_rest_parameter() {
+        compare(
+            "function foo(a, ...rest) {}",
+            "function foo(a: any, ...rest: any[]): void {}",
+        );
+    }
+
+    #[test]
+    fn add_types_rest_parameter_element_interface() {
+        compare(
+            "
+function foo(...args) {
+    args.forEach(arg => arg.name);
+    return args[0].id;
+}",
+            "
+interface Arg {
+    id: any,
+    name: any,
+}
+
+function foo(...args: Arg[]) {
+    args.forEach(arg => arg.name);
+    return args[0].id;
+}",
+        );
+    }
+
+    #[test]
+    fn add_types_rest_parameter_for_of() {
+        compare(
+            "
+function foo(...options) {
+    for (const option of options) {
+        option.enabled = true;
+    }
+}",
+            "
+interface Option {
+    enabled: boolean,
+}
+
+function foo(...options: Option[]): void {
+    for (const option of options) {
+        option.enabled = true;
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn add_types_array_pattern_parameter() {
+        compare(
+            "function foo([a, b], [c = 1, d = 'x']) {}",
+            "function foo([a, b]: [any, any], [c = 1, d = 'x']: [number, string]): void {}",
+        );
+    }
+
+    #[test]
+    fn add_types_array_pattern_nested_with_holes() {
+        compare(
+            "function foo([, a, [b, c = true], ...rest]) {}",
+            "function foo([, a, [b, c = true], ...rest]: [any, any, [any, boolean], ...any[]]): void {}",
+        );
+    }
+
+    #[test]
+    fn add_types_array_pattern_with_default() {
+        compare(
+            "function foo([a, b] = [1, 'x']) {}",
+            "function foo([a, b]: [number, string] = [1, 'x']): void {}",
+        );
+    }
+
+    #[test]
+    fn add_types_array_pattern_declarator() {
+        compare(
+            "const [x, , y] = [];",
+            "const [x, , y]: [any?, any?, any?] = [];",
+        );
+    }
+
+    #[test]
+    fn add_types_promise_parameter() {
+        compare(
+            "
+async function foo(a, b, c) {
+    await a;
+    b.then(done).catch(fail);
+    c.then(done);
+    c.value;
+}",
+            "
+interface C {
+    then: Function,
+    value: any,
+}
+
+async function foo(a: Promise<any>, b: Promise<any>, c: C): Promise<void> {
+    await a;
+    b.then(done).catch(fail);
+    c.then(done);
+    c.value;
+}",
+        );
+    }
+
+    #[test]
+    fn add_types_default_parameter_interface() {
+        compare(
+            "function render(opts = {}) { opts.width; opts.height }",
+            "
+interface Opts {
+    height?: any,
+    width?: any,
+}
+function render(opts: Opts = {}): void { opts.width; opts.height }",
+        );
+    }
+
+    #[test]
+    fn add_types_default_parameter_combines_literal() {
+        compare(
+            "function draw(shape = { sides: 3, name: 'tri' }) { shape.sides; shape.color }",
+            "
+interface Shape {
+    color?: any,
+    name: string,
+    sides: number,
+}
+function draw(shape: Shape = { sides: 3, name: 'tri' }): void { shape.sides; shape.color }",
+        );
+    }
+
+    #[test]
+    fn add_types_default_parameter_null() {
+        compare(
+            "function load(config = null) { config.url }",
+            "
+interface Config {
+    url: any,
+}
+function load(config: Config | null = null): void { config.url }",
+        );
+    }
+
+    #[test]
+    fn add_types_default_parameter_literal() {
+        compare(
+            "function f(a = 32) { a.toFixed() }",
+            "function f(a: number = 32): void { a.toFixed() }",
+        );
+    }
+
+    #[test]
+    fn add_types_destructured_defaults_are_optional() {
+        compare(
+            "
+function foo(options) {
+    const { size = 'large', count: total = 1, label } = options;
+}",
+            "
+interface Options {
+    count?: number,
+    label: any,
+    size?: string,
+}
+
+function foo(options: Options): void {
+    const { size = 'large', count: total = 1, label } = options;
+}",
+        );
+    }
+
+    #[test]
+    fn add_types_destructured_rest_element() {
+        compare(
+            "
+class Button extends React.Component {
+    render() {
+        const { size = 'md', ...rest } = this.props;
+    }
+}",
+            "
+interface Props {
+    size?: string,
+    [key: string]: any,
+}
+
+class Button extends React.Component<Props> {
+    render(): void {
+        const { size = 'md', ...rest } = this.props;
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn add_types_destructured_computed_key() {
+        compare(
+            "
+function foo(options) {
+    const { [key]: value, size } = options;
+}",
+            "
+interface Options {
+    size: any,
+    [key: string]: any,
+}
+
+function foo(options: Options): void {
+    const { [key]: value, size } = options;
+}",
+        );
+    }
+
+    #[test]
+    fn suggest_types_groups_changes() {
+        let suggestions = suggest_types(
+            "function test(options, b) {\n    options.enabled = true;\n}",
+            &ConversionOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            suggestions,
+            vec![
+                Suggestion {
+                    title: String::from("Annotate the return type of `test`"),
+                    span: 0..25,
+                    changes: vec![Change {
+                        offset: 25,
+                        text: String::from(": void"),
+                    }],
+                },
+                Suggestion {
+                    title: String::from(
+                        "Generate interface `Options` from the usages of `options`"
+                    ),
+                    span: 14..21,
+                    changes: vec![
+                        Change {
+                            offset: 0,
+                            text: String::from("\ninterface Options {\n    enabled: boolean,\n}\n"),
+                        },
+                        Change {
+                            offset: 21,
+                            text: String::from(": Options"),
+                        },
+                    ],
+                },
+                Suggestion {
+                    title: String::from("Annotate parameter `b`"),
+                    span: 23..24,
+                    changes: vec![Change {
+                        offset: 24,
+                        text: String::from(": any"),
+                    }],
+                },
+            ]
+        );
+    }
 }
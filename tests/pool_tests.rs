@@ -0,0 +1,55 @@
+mod tests {
+    use pretty_assertions::assert_eq;
+    use std::num::NonZeroUsize;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use type_rs::pool::process_all;
+
+    fn jobs(count: usize) -> NonZeroUsize {
+        NonZeroUsize::new(count).unwrap()
+    }
+
+    #[test]
+    fn process_all_preserves_order() {
+        let results = process_all((0..100).collect(), jobs(4), |item: usize| item * 2);
+
+        let expected: Vec<Result<usize, String>> = (0..100).map(|item| Ok(item * 2)).collect();
+        assert_eq!(results, expected);
+    }
+
+    #[test]
+    fn process_all_isolates_panics() {
+        let results = process_all(vec![1, 2, 3], jobs(2), |item: i32| {
+            if item == 2 {
+                panic!("item {item} is broken");
+            }
+            item
+        });
+
+        assert_eq!(
+            results,
+            vec![Ok(1), Err(String::from("item 2 is broken")), Ok(3)]
+        );
+    }
+
+    #[test]
+    fn process_all_respects_job_limit() {
+        let running = AtomicUsize::new(0);
+        let max_running = AtomicUsize::new(0);
+
+        process_all((0..50).collect(), jobs(3), |_: i32| {
+            let current = running.fetch_add(1, Ordering::SeqCst) + 1;
+            max_running.fetch_max(current, Ordering::SeqCst);
+            std::thread::sleep(std::time::Duration::from_millis(1));
+            running.fetch_sub(1, Ordering::SeqCst);
+        });
+
+        assert!(max_running.load(Ordering::SeqCst) <= 3);
+    }
+
+    #[test]
+    fn process_all_no_items() {
+        let results = process_all(Vec::<i32>::new(), jobs(4), |item| item);
+
+        assert!(results.is_empty());
+    }
+}
@@ -0,0 +1,67 @@
+mod tests {
+    use pretty_assertions::assert_eq;
+    use std::path::Path;
+    use std::time::Duration;
+    use type_rs::error::TypeRsError;
+    use type_rs::parser::ConversionSummary;
+    use type_rs::report::{FileReport, FileStatus};
+
+    #[test]
+    fn file_report_serializes_converted_file() {
+        let summary = ConversionSummary {
+            parameters: 2,
+            catch_clauses: 1,
+            interfaces: vec![String::from("Options")],
+            any_annotations: 1,
+            ..Default::default()
+        };
+        let report = FileReport::converted(
+            Path::new("src/a.js"),
+            Path::new("src/a.ts"),
+            FileStatus::Converted,
+            &summary,
+            Duration::from_millis(3),
+        );
+
+        assert_eq!(
+            serde_json::to_value(report).unwrap(),
+            serde_json::json!({
+                "source": "src/a.js",
+                "target": "src/a.ts",
+                "status": "converted",
+                "parameter_annotations": 2,
+                "variable_annotations": 0,
+                "catch_annotations": 1,
+                "interfaces": ["Options"],
+                "any_annotations": 1,
+                "elapsed_ms": 3.0,
+                "error": null,
+            })
+        );
+    }
+
+    #[test]
+    fn file_report_serializes_status() {
+        let report = FileReport::new(Path::new("src/a.js"), FileStatus::SkippedFlow);
+        let json = serde_json::to_value(report).unwrap();
+
+        assert_eq!(json["status"], "skipped_flow");
+        assert_eq!(json["target"], serde_json::Value::Null);
+        assert_eq!(json["elapsed_ms"], serde_json::Value::Null);
+    }
+
+    #[test]
+    fn file_report_records_error() {
+        let error = TypeRsError::UnexpectedSyntax {
+            message: String::from("parameter list outside of a function"),
+            span: 16..23,
+        };
+        let report = FileReport::failed(Path::new("src/a.js"), &error);
+
+        assert_eq!(report.status, FileStatus::Failed);
+        assert_eq!(
+            report.error.as_deref(),
+            Some("unexpected syntax tree: parameter list outside of a function (at 16..23)")
+        );
+    }
+}
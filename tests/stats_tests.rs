@@ -0,0 +1,127 @@
+mod common;
+
+mod tests {
+    use crate::common::create_directory;
+    use pretty_assertions::assert_eq;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use std::sync::Arc;
+    use type_rs::config::ConfigResolver;
+    use type_rs::pool::default_jobs;
+    use type_rs::stats::{collect_stats, file_stats, AnnotationStats};
+    use type_rs::traversal::FileFilter;
+
+    #[test]
+    fn file_stats_sorts_annotations() {
+        let stats = file_stats(
+            PathBuf::from("a.ts"),
+            true,
+            "interface Options { a: string }\nfunction f(options: Options, b: any, c: number, d) {\n    let x: string[] = [];\n    let y;\n    let z = 1;\n    for (let i = 0; i < 1; i++) {}\n}",
+        );
+
+        assert_eq!(
+            stats.parameters,
+            AnnotationStats {
+                any: 1,
+                interface: 1,
+                concrete: 1,
+                missing: 1,
+            }
+        );
+        assert_eq!(
+            stats.variables,
+            AnnotationStats {
+                any: 0,
+                interface: 0,
+                concrete: 1,
+                missing: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn file_stats_counts_fallback_types_as_any() {
+        let directory = create_directory("stats", "fallback");
+        fs::write(
+            directory.join("a.ts"),
+            "function f(a: Promise<any>, b: any, c: any[], d: unknown) {}",
+        )
+        .unwrap();
+
+        let filter = FileFilter::new(&[], &[]).unwrap();
+        let stats = collect_stats(
+            &directory,
+            &filter,
+            &Arc::new(ConfigResolver::new()),
+            default_jobs(),
+        );
+
+        assert_eq!(
+            stats.files[0].parameters,
+            AnnotationStats {
+                any: 3,
+                interface: 0,
+                concrete: 1,
+                missing: 0,
+            }
+        );
+        assert_eq!(stats.directories[0].coverage(), 0.25);
+    }
+
+    #[test]
+    fn collect_stats_rolls_up_directories() {
+        let directory = create_directory("stats", "rollup");
+        fs::create_dir_all(directory.join("src/nested")).unwrap();
+        fs::write(directory.join("src/nested/a.js"), "function f(a, b) {}").unwrap();
+        fs::write(
+            directory.join("src/b.ts"),
+            "function g(a: any, b: string) {}",
+        )
+        .unwrap();
+        fs::write(
+            directory.join("src/b.d.ts"),
+            "declare function h(a: any): void;",
+        )
+        .unwrap();
+
+        let filter = FileFilter::new(&[], &[]).unwrap();
+        let stats = collect_stats(
+            &directory,
+            &filter,
+            &Arc::new(ConfigResolver::new()),
+            default_jobs(),
+        );
+
+        let paths: Vec<&Path> = stats.files.iter().map(|file| file.path.as_path()).collect();
+        assert_eq!(
+            paths,
+            vec![Path::new("src/b.ts"), Path::new("src/nested/a.js")]
+        );
+
+        let directories: Vec<(&Path, usize, usize)> = stats
+            .directories
+            .iter()
+            .map(|directory| {
+                (
+                    directory.path.as_path(),
+                    directory.javascript_files,
+                    directory.typescript_files,
+                )
+            })
+            .collect();
+        assert_eq!(
+            directories,
+            vec![
+                (Path::new("."), 1, 1),
+                (Path::new("src"), 1, 1),
+                (Path::new("src/nested"), 1, 0),
+            ]
+        );
+
+        let root = &stats.directories[0];
+        assert_eq!(root.parameters.total(), 4);
+        assert_eq!(root.coverage(), 0.25);
+        assert_eq!(root.any_density(), 0.5);
+        assert_eq!(stats.directories[2].coverage(), 0.0);
+    }
+}
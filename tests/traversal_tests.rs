@@ -0,0 +1,155 @@
+mod common;
+
+mod tests {
+    use crate::common::create_tree;
+    use pretty_assertions::assert_eq;
+    use std::path::{Path, PathBuf};
+    use std::sync::Arc;
+    use type_rs::config::ConfigResolver;
+    use type_rs::traversal::{find_all_files, find_source_files, FileFilter};
+
+    fn relative(root: &Path, paths: &[PathBuf]) -> Vec<String> {
+        let mut paths: Vec<String> = paths
+            .iter()
+            .map(|path| {
+                path.strip_prefix(root)
+                    .unwrap()
+                    .to_string_lossy()
+                    .into_owned()
+            })
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    fn filter(includes: &[&str], excludes: &[&str]) -> FileFilter {
+        let to_strings = |globs: &[&str]| {
+            globs
+                .iter()
+                .map(|glob| glob.to_string())
+                .collect::<Vec<_>>()
+        };
+        FileFilter::new(&to_strings(includes), &to_strings(excludes)).unwrap()
+    }
+
+    #[test]
+    fn file_filter_default_excludes() {
+        let filter = filter(&[], &[]);
+
+        assert!(filter.is_excluded(Path::new("node_modules")));
+        assert!(filter.is_excluded(Path::new("packages/app/node_modules")));
+        assert!(filter.is_excluded(Path::new("dist")));
+        assert!(filter.is_excluded(Path::new("src/jquery.min.js")));
+        assert!(!filter.is_excluded(Path::new("src/index.js")));
+    }
+
+    #[test]
+    fn file_filter_custom_excludes() {
+        let filter = filter(&[], &["**/generated", "legacy/*.js"]);
+
+        assert!(filter.is_excluded(Path::new("src/generated")));
+        assert!(filter.is_excluded(Path::new("legacy/index.js")));
+        assert!(filter.is_excluded(Path::new("dist")));
+        assert!(!filter.is_excluded(Path::new("src/index.js")));
+    }
+
+    #[test]
+    fn file_filter_includes() {
+        let filter = filter(&["src/**/*.jsx"], &[]);
+
+        assert!(filter.is_included(Path::new("src/components/Button.jsx")));
+        assert!(!filter.is_included(Path::new("src/index.js")));
+        assert!(!filter.is_included(Path::new("test/Button.jsx")));
+    }
+
+    #[test]
+    fn file_filter_no_includes_accepts_everything() {
+        let filter = filter(&[], &[]);
+
+        assert!(filter.is_included(Path::new("src/index.js")));
+    }
+
+    #[test]
+    fn file_filter_invalid_glob() {
+        assert!(FileFilter::new(&[], &["src/[".to_string()]).is_err());
+    }
+
+    #[test]
+    fn file_filter_typescript() {
+        let javascript = filter(&[], &[]);
+        let typescript = filter(&[], &[]).with_typescript(true);
+
+        assert!(javascript.is_source_file(Path::new("src/index.jsx")));
+        assert!(!javascript.is_source_file(Path::new("src/index.ts")));
+        assert!(typescript.is_source_file(Path::new("src/index.js")));
+        assert!(typescript.is_source_file(Path::new("src/index.ts")));
+        assert!(typescript.is_source_file(Path::new("src/app.tsx")));
+        assert!(!typescript.is_source_file(Path::new("src/index.d.ts")));
+        assert!(!typescript.is_source_file(Path::new("src/styles.css")));
+    }
+
+    #[test]
+    fn find_source_files_walks_tree() {
+        let root = create_tree(
+            "traversal",
+            "walk",
+            &[
+                ("src/index.js", ""),
+                ("src/styles.css", ""),
+                (".storybook/main.js", ""),
+                ("generated/api.js", ""),
+                ("node_modules/react/index.js", ""),
+                (".git/hooks/update.js", ""),
+                (".type-rs-cache/entry.js", ""),
+                ("ignored.js", ""),
+                (".gitignore", "ignored.js\n"),
+            ],
+        );
+
+        let traversal = find_source_files(
+            &root,
+            &filter(&[], &["generated"]),
+            &Arc::new(ConfigResolver::new()),
+        );
+        let files: Vec<PathBuf> = traversal
+            .files
+            .iter()
+            .map(|file| file.path.clone())
+            .collect();
+
+        assert_eq!(
+            relative(&root, &files),
+            vec![".storybook/main.js", "src/index.js"]
+        );
+        assert_eq!(
+            relative(&root, &traversal.excluded),
+            vec!["generated", "node_modules"]
+        );
+    }
+
+    #[test]
+    fn find_all_files_includes_excluded_and_ignored_files() {
+        let root = create_tree(
+            "traversal",
+            "all",
+            &[
+                ("src/index.js", ""),
+                ("node_modules/react/index.js", ""),
+                (".git/HEAD", ""),
+                ("ignored.js", ""),
+                (".gitignore", "ignored.js\n"),
+                ("out/index.ts", ""),
+            ],
+        );
+
+        assert_eq!(
+            relative(&root, &find_all_files(&root, &root.join("out"))),
+            vec![
+                ".gitignore",
+                "ignored.js",
+                "node_modules/react/index.js",
+                "src/index.js"
+            ]
+        );
+    }
+}
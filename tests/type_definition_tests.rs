@@ -34,6 +34,7 @@ mod tests {
                 TypeDefinition::new("grandchildprop".into(), None),
                 TypeDefinition::new("grandchildprop2".into(), None),
             ]),
+            optional: false,
         });
 
         let children = get_children(&root);
@@ -49,6 +50,7 @@ mod tests {
         root.add_field(&mut TypeDefinition {
             name: "childprop".into(),
             ts_type: TypeDef::SimpleType(None),
+            optional: false,
         });
 
         let children = get_children(&root);
@@ -63,6 +65,7 @@ mod tests {
         root.add_field(&mut TypeDefinition {
             name: "childprop".into(),
             ts_type: TypeDef::NestedType(vec![TypeDefinition::new("grandkiddie".into(), None)]),
+            optional: false,
         });
 
         let children = get_children(&root);
@@ -0,0 +1,40 @@
+mod tests {
+    use pretty_assertions::assert_eq;
+    use type_rs::error::TypeRsError;
+    use type_rs::validation::{syntax_errors, validate_output, SyntaxError};
+
+    #[test]
+    fn syntax_errors_of_valid_source() {
+        assert_eq!(syntax_errors("function foo(a: any) {}"), vec![]);
+    }
+
+    #[test]
+    fn validate_output_accepts_valid_conversion() {
+        assert!(validate_output("function foo(a) {}", "function foo(a: any) {}").is_ok());
+    }
+
+    #[test]
+    fn validate_output_tolerates_existing_errors() {
+        assert!(validate_output(
+            "function foo(a) {\n  let x = ;\n}",
+            "function foo(a: any) {\n  let x = ;\n}"
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn validate_output_reports_new_errors() {
+        let result = validate_output("function foo(a) {}\n", "function foo(a) {}\nlet x: = 1;\n");
+
+        match result {
+            Err(TypeRsError::InvalidOutput { errors }) => {
+                assert!(!errors.is_empty());
+                for SyntaxError { line, span, .. } in &errors {
+                    assert_eq!(*line, 2);
+                    assert!(span.start >= 19);
+                }
+            }
+            result => panic!("Expected invalid output, got {result:?}"),
+        }
+    }
+}
@@ -0,0 +1,88 @@
+mod common;
+
+mod tests {
+    use crate::common::create_directory;
+    use pretty_assertions::assert_eq;
+    use std::fs;
+    use std::io::{BufRead, BufReader};
+    use std::path::Path;
+    use std::process::{Child, Command, Stdio};
+    use std::sync::mpsc::{self, Receiver};
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    /// Starts `type-rs watch` and forwards every line it logs
+    fn start_watching(directory: &Path) -> (Child, Receiver<String>) {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_type-rs"))
+            .current_dir(directory)
+            .env("RUST_LOG", "info")
+            .args(["watch", ".", "--no-cache"])
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+
+        let (sender, receiver) = mpsc::channel();
+        let stderr = BufReader::new(child.stderr.take().unwrap());
+        thread::spawn(move || {
+            for line in stderr.lines().map_while(Result::ok) {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        (child, receiver)
+    }
+
+    /// Collects logged lines until one contains `needle`, giving up after a few seconds
+    fn wait_for(lines: &Receiver<String>, needle: &str, seen: &mut Vec<String>) -> bool {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while let Some(timeout) = deadline.checked_duration_since(Instant::now()) {
+            match lines.recv_timeout(timeout) {
+                Ok(line) => {
+                    let found = line.contains(needle);
+                    seen.push(line);
+                    if found {
+                        return true;
+                    }
+                }
+                Err(_) => return false,
+            }
+        }
+
+        false
+    }
+
+    #[test]
+    fn watch_converts_new_files_once() {
+        let directory = create_directory("watch", "convert");
+        let (mut child, lines) = start_watching(&directory);
+        let mut seen = vec![];
+
+        assert!(wait_for(&lines, "Watching", &mut seen), "{seen:?}");
+        // The watcher is registered right after the announcement
+        thread::sleep(Duration::from_millis(500));
+        fs::write(directory.join("a.js"), "function f(a) {}").unwrap();
+
+        let converted = wait_for(&lines, "Converted", &mut seen);
+        // Give writing `a.ts` time to come back around as an event of its own
+        thread::sleep(Duration::from_secs(1));
+        seen.extend(lines.try_iter());
+        child.kill().unwrap();
+        child.wait().unwrap();
+
+        assert!(converted, "{seen:?}");
+        assert_eq!(
+            fs::read_to_string(directory.join("a.ts")).unwrap(),
+            "function f(a: any): void {}"
+        );
+        assert!(!directory.join("a.js").exists());
+        assert_eq!(
+            seen.iter()
+                .filter(|line| line.contains("Converted"))
+                .count(),
+            1,
+            "{seen:?}"
+        );
+    }
+}